@@ -70,5 +70,5 @@ fn main() {
 fn glow(c: u8, light_bg: bool) {
     let base = if light_bg { Color::Black } else { Color::White };
     let style = base.on(Color::Fixed(c));
-    print!("{}", style.paint(&format!(" {:3} ", c)));
+    print!("{}", style.paint(format!(" {:3} ", c)));
 }
@@ -1,11 +1,13 @@
 #![allow(missing_docs)]
-use crate::style::{Color, Style};
+use crate::attributes::Attributes;
+use crate::style::{Color, Style, UnderlineStyle};
+use crate::terminal::{colors_enabled, Transitions};
 use crate::write::AnyWrite;
 use std::fmt;
 
 impl Style {
     /// Write any bytes that go *before* a piece of text to the given writer.
-    fn write_prefix<W: AnyWrite + ?Sized>(&self, f: &mut W) -> Result<(), W::Error> {
+    pub(crate) fn write_prefix<W: AnyWrite + ?Sized>(&self, f: &mut W) -> Result<(), W::Error> {
         // If there are actually no styles here, then don’t write *any* codes
         // as the prefix. An empty ANSI code may not affect the terminal
         // output at all, but a user may just want a code-free string.
@@ -21,43 +23,52 @@ impl Style {
         // Write the codes’ prefix, then write numbers, separated by
         // semicolons, for each text style we want to apply.
         write!(f, "\x1B[")?;
-        let mut written_anything = false;
+        self.write_params(f, false, true)?;
 
+        // All the codes end with an `m`, because reasons.
+        write!(f, "m")?;
+
+        Ok(())
+    }
+
+    /// Write the numbers for each text style of this style, separated by
+    /// semicolons. If `written_anything` is true, there are already numbers
+    /// before them, so a semicolon is written first.
+    ///
+    /// If `subparams` is false, nothing is separated by colons, so the
+    /// underline is always a plain `4` and a 24-bit underline color is
+    /// written as `58;2;r;g;b`.
+    fn write_params<W: AnyWrite + ?Sized>(
+        &self,
+        f: &mut W,
+        mut written_anything: bool,
+        subparams: bool,
+    ) -> Result<(), W::Error> {
         {
-            let mut write_char = |c| {
+            let mut write_char = |c: &str| {
                 if written_anything {
                     write!(f, ";")?;
                 }
                 written_anything = true;
+                // GNU pads the codes that are a single digit to two digits.
                 #[cfg(feature = "gnu_legacy")]
-                write!(f, "0")?;
+                {
+                    if c.len() == 1 || c.as_bytes()[1] == b':' {
+                        write!(f, "0")?;
+                    }
+                }
                 write!(f, "{}", c)?;
                 Ok(())
             };
 
-            if self.is_bold {
-                write_char('1')?
-            }
-            if self.is_dimmed {
-                write_char('2')?
-            }
-            if self.is_italic {
-                write_char('3')?
-            }
-            if self.is_underline {
-                write_char('4')?
-            }
-            if self.is_blink {
-                write_char('5')?
-            }
-            if self.is_reverse {
-                write_char('7')?
-            }
-            if self.is_hidden {
-                write_char('8')?
-            }
-            if self.is_strikethrough {
-                write_char('9')?
+            for (attribute, code) in ON_CODES {
+                if self.attributes.contains(attribute) {
+                    if attribute == Attributes::UNDERLINE && subparams {
+                        write_char(self.underline_style.param())?
+                    } else {
+                        write_char(code)?
+                    }
+                }
             }
         }
 
@@ -80,6 +91,7 @@ impl Style {
                 if written_anything {
                     write!(f, ";")?;
                 }
+                written_anything = true;
                 bg.write_background_code(f)?;
             }
         }
@@ -97,17 +109,23 @@ impl Style {
                 if written_anything {
                     write!(f, ";")?;
                 }
+                written_anything = true;
                 fg.write_foreground_code(f)?;
             }
         }
-        // All the codes end with an `m`, because reasons.
-        write!(f, "m")?;
+
+        if let Some(ul) = self.underline_color {
+            if written_anything {
+                write!(f, ";")?;
+            }
+            ul.write_underline_code(f, subparams)?;
+        }
 
         Ok(())
     }
 
     /// Write any bytes that go *after* a piece of text to the given writer.
-    fn write_suffix<W: AnyWrite + ?Sized>(&self, f: &mut W) -> Result<(), W::Error> {
+    pub(crate) fn write_suffix<W: AnyWrite + ?Sized>(&self, f: &mut W) -> Result<(), W::Error> {
         if self.is_plain() {
             Ok(())
         } else {
@@ -116,6 +134,94 @@ impl Style {
     }
 }
 
+/// The code that turns on each attribute, in the order they’re written.
+const ON_CODES: [(Attributes, &str); 14] = [
+    (Attributes::BOLD, "1"),
+    (Attributes::DIMMED, "2"),
+    (Attributes::ITALIC, "3"),
+    (Attributes::UNDERLINE, "4"),
+    (Attributes::BLINK, "5"),
+    (Attributes::RAPID_BLINK, "6"),
+    (Attributes::REVERSE, "7"),
+    (Attributes::HIDDEN, "8"),
+    (Attributes::STRIKETHROUGH, "9"),
+    (Attributes::FRAMED, "51"),
+    (Attributes::ENCIRCLED, "52"),
+    (Attributes::OVERLINE, "53"),
+    (Attributes::SUPERSCRIPT, "73"),
+    (Attributes::SUBSCRIPT, "74"),
+];
+
+/// The code that turns off each group of attributes. Some of these turn off
+/// more than one: there’s no code that only turns off bold or dimmed text,
+/// for example, as 22 turns off both.
+pub(crate) const OFF_CODES: [(Attributes, u8); 10] = [
+    (Attributes::BOLD.union(Attributes::DIMMED), 22),
+    (Attributes::ITALIC, 23),
+    (Attributes::UNDERLINE, 24),
+    (Attributes::BLINK.union(Attributes::RAPID_BLINK), 25),
+    (Attributes::REVERSE, 27),
+    (Attributes::HIDDEN, 28),
+    (Attributes::STRIKETHROUGH, 29),
+    (Attributes::FRAMED.union(Attributes::ENCIRCLED), 54),
+    (Attributes::OVERLINE, 55),
+    (Attributes::SUPERSCRIPT.union(Attributes::SUBSCRIPT), 75),
+];
+
+/// Write the bytes that turn off the text styles and colors that are set in
+/// `removed`, then turn on the ones that are set in `added`, as a single code.
+/// If `removed` has only one of a group of attributes that are turned off
+/// together, any others that should stay have to be in `added`.
+pub(crate) fn write_transition<W: AnyWrite + ?Sized>(
+    removed: &Style,
+    added: &Style,
+    f: &mut W,
+) -> Result<(), W::Error> {
+    write!(f, "\x1B[")?;
+    let mut written_anything = false;
+
+    {
+        let mut write_code = |code| {
+            if written_anything {
+                write!(f, ";")?;
+            }
+            written_anything = true;
+            write!(f, "{}", code)
+        };
+
+        for (attributes, code) in OFF_CODES {
+            if removed.attributes.intersects(attributes) {
+                write_code(code)?
+            }
+        }
+        if removed.foreground.is_some() {
+            write_code(39)?
+        }
+        if removed.background.is_some() {
+            write_code(49)?
+        }
+        if removed.underline_color.is_some() {
+            write_code(59)?
+        }
+    }
+
+    added.write_params(f, written_anything, true)?;
+    write!(f, "m")
+}
+
+impl UnderlineStyle {
+    /// The parameter that turns on underlining with this kind of line.
+    fn param(self) -> &'static str {
+        match self {
+            UnderlineStyle::Single => "4",
+            UnderlineStyle::Double => "4:2",
+            UnderlineStyle::Curly => "4:3",
+            UnderlineStyle::Dotted => "4:4",
+            UnderlineStyle::Dashed => "4:5",
+        }
+    }
+}
+
 /// The code to send to reset all styles and return to `Style::default()`.
 pub static RESET: &str = "\x1B[0m";
 
@@ -146,6 +252,38 @@ impl Color {
         }
     }
 
+    /// Underline colors only have codes for 256-color and 24-bit colors,
+    /// so the named colors are written as their palette entries.
+    fn write_underline_code<W: AnyWrite + ?Sized>(
+        &self,
+        f: &mut W,
+        subparams: bool,
+    ) -> Result<(), W::Error> {
+        let num = match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Purple | Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::DarkGray => 8,
+            Color::LightRed => 9,
+            Color::LightGreen => 10,
+            Color::LightYellow => 11,
+            Color::LightBlue => 12,
+            Color::LightPurple | Color::LightMagenta => 13,
+            Color::LightCyan => 14,
+            Color::LightGray => 15,
+            Color::Fixed(num) => *num,
+            Color::Rgb(r, g, b) if subparams => return write!(f, "58:2::{}:{}:{}", r, g, b),
+            Color::Rgb(r, g, b) => return write!(f, "58;2;{};{};{}", r, g, b),
+            Color::Default => return write!(f, "59"),
+        };
+        write!(f, "58;5;{}", num)
+    }
+
     fn write_background_code<W: AnyWrite + ?Sized>(&self, f: &mut W) -> Result<(), W::Error> {
         match self {
             Color::Black => write!(f, "40"),
@@ -180,7 +318,7 @@ impl Color {
 /// string with the `.to_string()` method. For examples, see
 /// [`Style::prefix`](struct.Style.html#method.prefix).
 #[derive(Clone, Copy, Debug)]
-pub struct Prefix(Style);
+pub struct Prefix(pub(crate) Style);
 
 /// Like `AnsiString`, but only displays the difference between two
 /// styles.
@@ -190,7 +328,7 @@ pub struct Prefix(Style);
 /// string with the `.to_string()` method. For examples, see
 /// [`Style::infix`](struct.Style.html#method.infix).
 #[derive(Clone, Copy, Debug)]
-pub struct Infix(Style, Style);
+pub struct Infix(pub(crate) Style, pub(crate) Style);
 
 /// Like `AnsiString`, but only displays the style suffix.
 ///
@@ -199,7 +337,21 @@ pub struct Infix(Style, Style);
 /// string with the `.to_string()` method. For examples, see
 /// [`Style::suffix`](struct.Style.html#method.suffix).
 #[derive(Clone, Copy, Debug)]
-pub struct Suffix(Style);
+pub struct Suffix(pub(crate) Style);
+
+/// Only the parameters of the code that turns a style on, separated by
+/// semicolons, without the `ESC [` before them or the `m` after them.
+///
+/// No colon-separated subparameters are written, so every kind of underline
+/// is written as a plain `4`, and underline colors use the `58;5;n` and
+/// `58;2;r;g;b` forms.
+///
+/// This type implements the `Display` trait, meaning it can be written to a
+/// `std::fmt` formatting without doing any extra allocation, and written to a
+/// string with the `.to_string()` method. For examples, see
+/// [`Style::sgr_params`](struct.Style.html#method.sgr_params).
+#[derive(Clone, Copy, Debug)]
+pub struct SgrParams(pub(crate) Style);
 
 impl Style {
     /// The prefix bytes for this style. These are the bytes that tell the
@@ -309,6 +461,45 @@ impl Style {
     pub const fn suffix(self) -> Suffix {
         Suffix(self)
     }
+
+    /// The parameters of the code that turns this style on, in the form used
+    /// by environment variables such as `LS_COLORS` and `GREP_COLORS`. A
+    /// style with [`prefix_with_reset`](struct.Style.html#structfield.prefix_with_reset)
+    /// set starts with a `0`, and a plain style has no parameters at all.
+    ///
+    /// Unlike the prefix, these are written even when colors have been
+    /// turned off with [`set_colors_enabled`](fn.set_colors_enabled.html),
+    /// as they are meant for other programs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "gnu_legacy"))]
+    /// # {
+    /// use nu_ansi_term::{Style, Color::Blue};
+    ///
+    /// let style = Blue.bold();
+    /// assert_eq!("1;34",
+    ///            style.sgr_params().to_string());
+    ///
+    /// let ls_colors = format!("di={}", style.sgr_params());
+    /// assert_eq!("di=1;34", ls_colors);
+    /// # }
+    /// ```
+    /// # Examples with gnu_legacy feature enabled
+    /// Styles like bold, underlined, etc. are two-digit now
+    /// ```
+    /// # #[cfg(feature = "gnu_legacy")]
+    /// # {
+    /// use nu_ansi_term::Color::Blue;
+    ///
+    /// assert_eq!("01;34",
+    ///            Blue.bold().sgr_params().to_string());
+    /// # }
+    /// ```
+    pub const fn sgr_params(self) -> SgrParams {
+        SgrParams(self)
+    }
 }
 
 impl Color {
@@ -365,8 +556,34 @@ impl Color {
     }
 }
 
+impl Infix {
+    /// Write the bytes that change from the first style to the second.
+    pub(crate) fn write_to_any<W: AnyWrite + ?Sized>(
+        &self,
+        w: &mut W,
+        transitions: Transitions,
+    ) -> Result<(), W::Error> {
+        use crate::difference::Difference;
+
+        match Difference::between_with(&self.0, &self.1, transitions) {
+            Difference::ExtraStyles(style) => style.write_prefix(w),
+            Difference::Reset => {
+                write!(w, "{}", RESET)?;
+                self.1.write_prefix(w)
+            }
+            Difference::Transition { removed, added } => write_transition(&removed, &added, w),
+            Difference::Empty => {
+                Ok(()) // nothing to write
+            }
+        }
+    }
+}
+
 impl fmt::Display for Prefix {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !colors_enabled() {
+            return Ok(());
+        }
         let f: &mut dyn fmt::Write = f;
         self.0.write_prefix(f)
     }
@@ -374,31 +591,47 @@ impl fmt::Display for Prefix {
 
 impl fmt::Display for Infix {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        use crate::difference::Difference;
-
-        match Difference::between(&self.0, &self.1) {
-            Difference::ExtraStyles(style) => {
-                let f: &mut dyn fmt::Write = f;
-                style.write_prefix(f)
-            }
-            Difference::Reset => {
-                let f: &mut dyn fmt::Write = f;
-                write!(f, "{}{}", RESET, self.1.prefix())
-            }
-            Difference::Empty => {
-                Ok(()) // nothing to write
-            }
+        if !colors_enabled() {
+            return Ok(());
         }
+        let f: &mut dyn fmt::Write = f;
+        self.write_to_any(f, Transitions::Reset)
     }
 }
 
 impl fmt::Display for Suffix {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !colors_enabled() {
+            return Ok(());
+        }
         let f: &mut dyn fmt::Write = f;
         self.0.write_suffix(f)
     }
 }
 
+impl fmt::Display for SgrParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let f: &mut dyn fmt::Write = f;
+        if self.0.is_plain() {
+            return Ok(());
+        }
+        if self.0.prefix_with_reset {
+            write!(
+                f,
+                "{}",
+                if cfg!(feature = "gnu_legacy") {
+                    "00"
+                } else {
+                    "0"
+                }
+            )?;
+        }
+        // `LS_COLORS` and `GREP_COLORS` separate their entries with colons,
+        // so the parameters here are written without any.
+        self.0.write_params(f, self.0.prefix_with_reset, false)
+    }
+}
+
 #[cfg(test)]
 macro_rules! test {
     ($name: ident: $style: expr; $input: expr => $result: expr) => {
@@ -416,8 +649,9 @@ macro_rules! test {
 #[cfg(test)]
 #[cfg(not(feature = "gnu_legacy"))]
 mod test {
+    use crate::attributes::Attributes;
     use crate::style::Color::*;
-    use crate::style::Style;
+    use crate::style::{Style, UnderlineStyle};
     use crate::Color;
     use std::default::Default;
 
@@ -453,6 +687,25 @@ mod test {
     test!(bold_with_reset_2:     Style::new().bold().reset_before_style(); "hi" => "\x1B[0m\x1B[1mhi\x1B[0m");
     test!(underline:             Style::new().underline();          "hi" => "\x1B[4mhi\x1B[0m");
     test!(bunderline:            Style::new().bold().underline();   "hi" => "\x1B[1;4mhi\x1B[0m");
+    test!(underline_double:      Style::new().underline_style(UnderlineStyle::Double); "hi" => "\x1B[4:2mhi\x1B[0m");
+    test!(double_underline:      Red.double_underline();                               "hi" => "\x1B[4:2;31mhi\x1B[0m");
+    test!(rapid_blink:           Style::new().rapid_blink();        "hi" => "\x1B[6mhi\x1B[0m");
+    test!(framed:                Style::new().framed();             "hi" => "\x1B[51mhi\x1B[0m");
+    test!(encircled:             Style::new().encircled();          "hi" => "\x1B[52mhi\x1B[0m");
+    test!(overline:              Blue.overline();                   "hi" => "\x1B[53;34mhi\x1B[0m");
+    test!(superscript:           Style::new().superscript();        "hi" => "\x1B[73mhi\x1B[0m");
+    test!(subscript:             Style::new().subscript();          "hi" => "\x1B[74mhi\x1B[0m");
+    test!(bold_overline:         Style::new().bold().overline();    "hi" => "\x1B[1;53mhi\x1B[0m");
+    test!(underline_curly:       Red.underline_style(UnderlineStyle::Curly);           "hi" => "\x1B[4:3;31mhi\x1B[0m");
+    test!(underline_dotted:      Style::new().underline_style(UnderlineStyle::Dotted); "hi" => "\x1B[4:4mhi\x1B[0m");
+    test!(underline_dashed:      Style::new().underline_style(UnderlineStyle::Dashed); "hi" => "\x1B[4:5mhi\x1B[0m");
+    test!(underline_kind_only:   Style { attributes: Attributes::empty(), ..Red.underline_style(UnderlineStyle::Curly) }; "hi" => "\x1B[31mhi\x1B[0m");
+    test!(underline_kind_plain:  Style { attributes: Attributes::empty(), ..Style::new().underline_style(UnderlineStyle::Curly) }; "hi" => "hi");
+    test!(underline_named:       Style::new().underline().underline_color(Red);        "hi" => "\x1B[4;58;5;1mhi\x1B[0m");
+    test!(underline_bright:      Blue.underline().underline_color(LightPurple);        "hi" => "\x1B[4;34;58;5;13mhi\x1B[0m");
+    test!(underline_fixed:       Blue.on(Red).underline_color(Fixed(100));             "hi" => "\x1B[41;34;58;5;100mhi\x1B[0m");
+    test!(underline_rgb:         Style::new().underline_color(Rgb(1, 2, 3));           "hi" => "\x1B[58:2::1:2:3mhi\x1B[0m");
+    test!(underline_default:     Style::new().underline_color(Color::Default);         "hi" => "\x1B[59mhi\x1B[0m");
     test!(dimmed:                Style::new().dimmed();             "hi" => "\x1B[2mhi\x1B[0m");
     test!(italic:                Style::new().italic();             "hi" => "\x1B[3mhi\x1B[0m");
     test!(blink:                 Style::new().blink();              "hi" => "\x1B[5mhi\x1B[0m");
@@ -461,6 +714,28 @@ mod test {
     test!(stricken:              Style::new().strikethrough();      "hi" => "\x1B[9mhi\x1B[0m");
     test!(lr_on_lr:              LightRed.on(LightRed);             "hi" => "\x1B[101;91mhi\x1B[0m");
 
+    #[test]
+    fn sgr_params() {
+        assert_eq!(Style::new().sgr_params().to_string(), "");
+        assert_eq!(Blue.bold().sgr_params().to_string(), "1;34");
+        assert_eq!(Purple.on(White).sgr_params().to_string(), "47;35");
+        assert_eq!(
+            Red.underline_style(UnderlineStyle::Curly)
+                .sgr_params()
+                .to_string(),
+            "4;31"
+        );
+        assert_eq!(
+            Style::new()
+                .underline()
+                .underline_color(Rgb(1, 2, 3))
+                .sgr_params()
+                .to_string(),
+            "4;58;2;1;2;3"
+        );
+        assert_eq!(Red.reset_before_style().sgr_params().to_string(), "0;31");
+    }
+
     #[test]
     fn test_infix() {
         assert_eq!(
@@ -474,6 +749,19 @@ mod test {
         assert_eq!(White.normal().infix(White.bold()).to_string(), "\x1B[1m");
         assert_eq!(White.normal().infix(Blue.normal()).to_string(), "\x1B[34m");
         assert_eq!(Blue.bold().infix(Blue.bold()).to_string(), "");
+        assert_eq!(
+            Blue.underline()
+                .infix(Blue.underline().underline_color(Red))
+                .to_string(),
+            "\x1B[58;5;1m"
+        );
+        assert_eq!(
+            Blue.underline()
+                .underline_color(Red)
+                .infix(Blue.underline())
+                .to_string(),
+            "\x1B[0m\x1B[4;34m"
+        );
     }
 
     #[test]
@@ -494,7 +782,7 @@ mod test {
 #[cfg(feature = "gnu_legacy")]
 mod gnu_legacy_test {
     use crate::style::Color::*;
-    use crate::style::Style;
+    use crate::style::{Style, UnderlineStyle};
     use crate::Color;
     use std::default::Default;
 
@@ -529,6 +817,9 @@ mod gnu_legacy_test {
     test!(bold_with_reset_2:     Style::new().bold().reset_before_style(); "hi" => "\x1B[0m\x1B[01mhi\x1B[0m");
     test!(underline:             Style::new().underline();          "hi" => "\x1B[04mhi\x1B[0m");
     test!(bunderline:            Style::new().bold().underline();   "hi" => "\x1B[01;04mhi\x1B[0m");
+    test!(underline_curly:       Style::new().underline_style(UnderlineStyle::Curly); "hi" => "\x1B[04:3mhi\x1B[0m");
+    test!(double_underline:      Style::new().double_underline();   "hi" => "\x1B[04:2mhi\x1B[0m");
+    test!(bold_overline:         Style::new().bold().overline();    "hi" => "\x1B[01;53mhi\x1B[0m");
     test!(dimmed:                Style::new().dimmed();             "hi" => "\x1B[02mhi\x1B[0m");
     test!(italic:                Style::new().italic();             "hi" => "\x1B[03mhi\x1B[0m");
     test!(blink:                 Style::new().blink();              "hi" => "\x1B[05mhi\x1B[0m");
@@ -537,6 +828,20 @@ mod gnu_legacy_test {
     test!(stricken:              Style::new().strikethrough();      "hi" => "\x1B[09mhi\x1B[0m");
     test!(lr_on_lr:              LightRed.on(LightRed);             "hi" => "\x1B[91;101mhi\x1B[0m");
 
+    #[test]
+    fn sgr_params() {
+        assert_eq!(Style::new().sgr_params().to_string(), "");
+        assert_eq!(Blue.bold().sgr_params().to_string(), "01;34");
+        assert_eq!(Purple.on(White).sgr_params().to_string(), "35;47");
+        assert_eq!(
+            Red.underline_style(UnderlineStyle::Curly)
+                .sgr_params()
+                .to_string(),
+            "04;31"
+        );
+        assert_eq!(Red.reset_before_style().sgr_params().to_string(), "00;31");
+    }
+
     #[test]
     fn test_write_prefix_gnu_compat_order() {
         let style = Style {
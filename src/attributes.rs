@@ -0,0 +1,259 @@
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+/// A set of the text attributes of a `Style`, such as bold or underlined,
+/// stored as the bits of a single number.
+///
+/// Sets can be combined with `|` (union), `&` (intersection) and `-`
+/// (difference), and checked with [`Attributes::contains`].
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{Attributes, Style};
+///
+/// let style = Style::new().bold().italic();
+/// assert!(style.attributes.contains(Attributes::BOLD | Attributes::ITALIC));
+/// assert_eq!(style.attributes - Attributes::BOLD, Attributes::ITALIC);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Attributes(u16);
+
+impl Attributes {
+    /// Bold text (code `1`).
+    pub const BOLD: Attributes = Attributes(1 << 0);
+
+    /// Dimmed text (code `2`).
+    pub const DIMMED: Attributes = Attributes(1 << 1);
+
+    /// Italic text (code `3`).
+    pub const ITALIC: Attributes = Attributes(1 << 2);
+
+    /// Underlined text (code `4`). The kind of line is given by the
+    /// `underline_style` of the `Style`.
+    pub const UNDERLINE: Attributes = Attributes(1 << 3);
+
+    /// Blinking text (code `5`).
+    pub const BLINK: Attributes = Attributes(1 << 4);
+
+    /// Rapidly blinking text (code `6`).
+    pub const RAPID_BLINK: Attributes = Attributes(1 << 5);
+
+    /// Text with the foreground and background colors swapped (code `7`).
+    pub const REVERSE: Attributes = Attributes(1 << 6);
+
+    /// Hidden text (code `8`).
+    pub const HIDDEN: Attributes = Attributes(1 << 7);
+
+    /// Struckthrough text (code `9`).
+    pub const STRIKETHROUGH: Attributes = Attributes(1 << 8);
+
+    /// Framed text (code `51`).
+    pub const FRAMED: Attributes = Attributes(1 << 9);
+
+    /// Encircled text (code `52`).
+    pub const ENCIRCLED: Attributes = Attributes(1 << 10);
+
+    /// Overlined text (code `53`).
+    pub const OVERLINE: Attributes = Attributes(1 << 11);
+
+    /// Superscript text (code `73`).
+    pub const SUPERSCRIPT: Attributes = Attributes(1 << 12);
+
+    /// Subscript text (code `74`).
+    pub const SUBSCRIPT: Attributes = Attributes(1 << 13);
+
+    /// Every attribute with its name, in alphabetical order.
+    pub(crate) const NAMED: [(&'static str, Attributes); 14] = [
+        ("blink", Attributes::BLINK),
+        ("bold", Attributes::BOLD),
+        ("dimmed", Attributes::DIMMED),
+        ("encircled", Attributes::ENCIRCLED),
+        ("framed", Attributes::FRAMED),
+        ("hidden", Attributes::HIDDEN),
+        ("italic", Attributes::ITALIC),
+        ("overline", Attributes::OVERLINE),
+        ("rapid_blink", Attributes::RAPID_BLINK),
+        ("reverse", Attributes::REVERSE),
+        ("strikethrough", Attributes::STRIKETHROUGH),
+        ("subscript", Attributes::SUBSCRIPT),
+        ("superscript", Attributes::SUPERSCRIPT),
+        ("underline", Attributes::UNDERLINE),
+    ];
+
+    /// Returns a set with no attributes in it.
+    pub const fn empty() -> Attributes {
+        Attributes(0)
+    }
+
+    /// Returns a set with every attribute in it.
+    pub const fn all() -> Attributes {
+        Attributes((1 << 14) - 1)
+    }
+
+    /// Returns the bits that represent this set.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the set represented by the given bits, ignoring any that
+    /// don’t belong to an attribute.
+    pub const fn from_bits_truncate(bits: u16) -> Attributes {
+        Attributes(bits & Attributes::all().0)
+    }
+
+    /// Returns whether there are no attributes in this set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether every attribute in `other` is in this set.
+    pub const fn contains(self, other: Attributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether any attribute in `other` is in this set.
+    pub const fn intersects(self, other: Attributes) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns the attributes that are in either set.
+    pub const fn union(self, other: Attributes) -> Attributes {
+        Attributes(self.0 | other.0)
+    }
+
+    /// Returns the attributes that are in both sets.
+    pub const fn intersection(self, other: Attributes) -> Attributes {
+        Attributes(self.0 & other.0)
+    }
+
+    /// Returns the attributes that are in this set but not in `other`.
+    pub const fn difference(self, other: Attributes) -> Attributes {
+        Attributes(self.0 & !other.0)
+    }
+
+    /// Adds the attributes in `other` to this set.
+    pub fn insert(&mut self, other: Attributes) {
+        self.0 |= other.0;
+    }
+
+    /// Removes the attributes in `other` from this set.
+    pub fn remove(&mut self, other: Attributes) {
+        self.0 &= !other.0;
+    }
+
+    /// Adds or removes the attributes in `other`, depending on `value`.
+    pub fn set(&mut self, other: Attributes, value: bool) {
+        if value {
+            self.insert(other)
+        } else {
+            self.remove(other)
+        }
+    }
+}
+
+impl BitOr for Attributes {
+    type Output = Attributes;
+
+    fn bitor(self, other: Attributes) -> Attributes {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for Attributes {
+    fn bitor_assign(&mut self, other: Attributes) {
+        self.insert(other)
+    }
+}
+
+impl BitAnd for Attributes {
+    type Output = Attributes;
+
+    fn bitand(self, other: Attributes) -> Attributes {
+        self.intersection(other)
+    }
+}
+
+impl BitAndAssign for Attributes {
+    fn bitand_assign(&mut self, other: Attributes) {
+        *self = self.intersection(other)
+    }
+}
+
+impl Sub for Attributes {
+    type Output = Attributes;
+
+    fn sub(self, other: Attributes) -> Attributes {
+        self.difference(other)
+    }
+}
+
+impl SubAssign for Attributes {
+    fn sub_assign(&mut self, other: Attributes) {
+        self.remove(other)
+    }
+}
+
+/// Attributes are shown by name, as in `Attributes(bold | italic)`.
+impl fmt::Debug for Attributes {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("Attributes(")?;
+        let mut written_anything = false;
+        for (name, attribute) in Attributes::NAMED {
+            if self.contains(attribute) {
+                if written_anything {
+                    fmt.write_str(" | ")?;
+                }
+                written_anything = true;
+                fmt.write_str(name)?;
+            }
+        }
+        fmt.write_str(")")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Attributes;
+
+    #[test]
+    fn set_operations() {
+        let both = Attributes::BOLD | Attributes::ITALIC;
+        assert!(both.contains(Attributes::BOLD));
+        assert!(!both.contains(Attributes::BOLD | Attributes::HIDDEN));
+        assert!(both.intersects(Attributes::BOLD | Attributes::HIDDEN));
+        assert_eq!(both & Attributes::ITALIC, Attributes::ITALIC);
+        assert_eq!(both - Attributes::ITALIC, Attributes::BOLD);
+        assert!((both - both).is_empty());
+        assert_eq!(Attributes::all() - Attributes::all(), Attributes::empty());
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut attributes = Attributes::empty();
+        attributes.insert(Attributes::BLINK);
+        attributes |= Attributes::OVERLINE;
+        attributes.set(Attributes::BLINK, false);
+        assert_eq!(attributes, Attributes::OVERLINE);
+        attributes -= Attributes::OVERLINE;
+        assert!(attributes.is_empty());
+    }
+
+    #[test]
+    fn bits() {
+        assert_eq!(Attributes::from_bits_truncate(u16::MAX), Attributes::all());
+        assert_eq!(
+            Attributes::from_bits_truncate(Attributes::SUBSCRIPT.bits()),
+            Attributes::SUBSCRIPT
+        );
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(format!("{:?}", Attributes::empty()), "Attributes()");
+        assert_eq!(
+            format!("{:?}", Attributes::ITALIC | Attributes::BOLD),
+            "Attributes(bold | italic)"
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use crate::rgb::Rgb;
+use crate::style::Color;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error from parsing a `Color` or an `Rgb` color out of a string.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::Color;
+///
+/// let error = "#12345".parse::<Color>().unwrap_err();
+/// assert_eq!(error.input(), "#12345");
+/// assert_eq!(
+///     error.to_string(),
+///     "invalid color `#12345`: hex colors need 3 or 6 digits"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError {
+    kind: ErrorKind,
+    input: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+    HexLength,
+    HexDigit,
+    RgbComponents,
+    IndexOutOfRange,
+    UnknownName,
+    NotRgb,
+}
+
+impl ParseColorError {
+    fn new(kind: ErrorKind, input: &str) -> ParseColorError {
+        ParseColorError {
+            kind,
+            input: input.to_owned(),
+        }
+    }
+
+    /// The string that could not be parsed.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid color `{}`: ", self.input)?;
+        f.write_str(match self.kind {
+            ErrorKind::HexLength => "hex colors need 3 or 6 digits",
+            ErrorKind::HexDigit => "expected only hex digits",
+            ErrorKind::RgbComponents => "expected three numbers from 0 to 255 in `rgb()`",
+            ErrorKind::IndexOutOfRange => "palette indices go from 0 to 255",
+            ErrorKind::UnknownName => {
+                "expected a color name, a palette index, `#rrggbb`, `0xrrggbb` or `rgb(r, g, b)`"
+            }
+            ErrorKind::NotRgb => "expected `#rrggbb`, `#rgb`, `0xrrggbb` or `rgb(r, g, b)`",
+        })
+    }
+}
+
+impl Error for ParseColorError {}
+
+/// Parses an `Rgb` color written as `#rrggbb`, `#rgb`, `0xrrggbb` or
+/// `rgb(r, g, b)`. With the `named_colors` feature, CSS and X11 color names
+/// are accepted as well: see [`named_color`](crate::named_color).
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::Rgb;
+///
+/// assert_eq!("#ff8000".parse(), Ok(Rgb::new(255, 128, 0)));
+/// assert_eq!("#f80".parse(), Ok(Rgb::new(255, 136, 0)));
+/// assert_eq!("0xFF8000".parse(), Ok(Rgb::new(255, 128, 0)));
+/// assert_eq!("rgb(255, 128, 0)".parse(), Ok(Rgb::new(255, 128, 0)));
+/// assert!("#ff80".parse::<Rgb>().is_err());
+/// ```
+impl FromStr for Rgb {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Rgb, ParseColorError> {
+        let error = |kind| ParseColorError::new(kind, s);
+        let trimmed = s.trim();
+        let lower = trimmed.to_ascii_lowercase();
+
+        let (digits, short_allowed) = if let Some(digits) = lower.strip_prefix('#') {
+            (digits, true)
+        } else if let Some(digits) = lower.strip_prefix("0x") {
+            (digits, false)
+        } else if let Some(args) = lower
+            .strip_prefix("rgb(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let components = args
+                .split(',')
+                .map(|c| c.trim().parse::<u8>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| error(ErrorKind::RgbComponents))?;
+            return match components[..] {
+                [r, g, b] => Ok(Rgb::new(r, g, b)),
+                _ => Err(error(ErrorKind::RgbComponents)),
+            };
+        } else {
+            #[cfg(feature = "named_colors")]
+            if let Some(rgb) = crate::named_colors::named_color(trimmed) {
+                return Ok(rgb);
+            }
+            return Err(error(ErrorKind::NotRgb));
+        };
+
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(error(ErrorKind::HexDigit));
+        }
+        let value = |range: std::ops::Range<usize>| u8::from_str_radix(&digits[range], 16).unwrap();
+        match digits.len() {
+            3 if short_allowed => Ok(Rgb::new(
+                value(0..1) * 17,
+                value(1..2) * 17,
+                value(2..3) * 17,
+            )),
+            6 => Ok(Rgb::new(value(0..2), value(2..4), value(4..6))),
+            _ => Err(error(ErrorKind::HexLength)),
+        }
+    }
+}
+
+/// Parses a `Color` from a name, a palette index from 0 to 255, or any of
+/// the forms accepted by `Rgb`.
+///
+/// Names are not case-sensitive, and may use underscores, hyphens or
+/// nothing between words. The bright colors can be written with either a
+/// `light` or a `bright` prefix, so `light_red`, `bright-red` and
+/// `BrightRed` are all `LightRed`; `bright_black` and `bright_white` are
+/// `DarkGray` and `LightGray`, and `grey` can be used for `gray`.
+///
+/// With the `named_colors` feature, any other CSS or X11 color name is
+/// parsed as an `Rgb` color, so `"steelblue"` is `Color::Rgb(70, 130, 180)`.
+/// The names above keep their meaning as terminal colors.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::Color;
+///
+/// assert_eq!("red".parse(), Ok(Color::Red));
+/// assert_eq!("Bright_Blue".parse(), Ok(Color::LightBlue));
+/// assert_eq!("208".parse(), Ok(Color::Fixed(208)));
+/// assert_eq!("#202020".parse(), Ok(Color::Rgb(32, 32, 32)));
+/// assert!("256".parse::<Color>().is_err());
+/// ```
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Color, ParseColorError> {
+        let trimmed = s.trim();
+        if !trimmed.is_empty() && trimmed.bytes().all(|b| b.is_ascii_digit()) {
+            return trimmed
+                .parse()
+                .map(Color::Fixed)
+                .map_err(|_| ParseColorError::new(ErrorKind::IndexOutOfRange, s));
+        }
+
+        let lower = trimmed.to_ascii_lowercase();
+        if lower.starts_with('#') || lower.starts_with("0x") || lower.starts_with("rgb(") {
+            return Rgb::from_str(s).map(|rgb| Color::Rgb(rgb.r, rgb.g, rgb.b));
+        }
+
+        let name = lower.replace(['_', '-', ' '], "").replace("grey", "gray");
+        if name == "darkgray" {
+            return Ok(Color::DarkGray);
+        }
+        let (bright, base) = match name
+            .strip_prefix("bright")
+            .or_else(|| name.strip_prefix("light"))
+        {
+            Some(base) => (true, base),
+            None => (false, &name[..]),
+        };
+        Ok(match (bright, base) {
+            (false, "default") => Color::Default,
+            (false, "black") => Color::Black,
+            (true, "black") => Color::DarkGray,
+            (false, "red") => Color::Red,
+            (true, "red") => Color::LightRed,
+            (false, "green") => Color::Green,
+            (true, "green") => Color::LightGreen,
+            (false, "yellow") => Color::Yellow,
+            (true, "yellow") => Color::LightYellow,
+            (false, "blue") => Color::Blue,
+            (true, "blue") => Color::LightBlue,
+            (false, "purple") => Color::Purple,
+            (true, "purple") => Color::LightPurple,
+            (false, "magenta") => Color::Magenta,
+            (true, "magenta") => Color::LightMagenta,
+            (false, "cyan") => Color::Cyan,
+            (true, "cyan") => Color::LightCyan,
+            (false, "white") => Color::White,
+            (true, "white") | (true, "gray") => Color::LightGray,
+            #[cfg(feature = "named_colors")]
+            _ if crate::named_colors::named_color(&name).is_some() => {
+                let rgb = crate::named_colors::named_color(&name).unwrap();
+                Color::Rgb(rgb.r, rgb.g, rgb.b)
+            }
+            _ => return Err(ParseColorError::new(ErrorKind::UnknownName, s)),
+        })
+    }
+}
+
+/// Writes a color the way it is parsed: by name, by palette index, or as
+/// `#rrggbb`.
+pub(crate) struct ColorSpec(pub(crate) Color);
+
+impl fmt::Display for ColorSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self.0 {
+            Color::Fixed(n) => return write!(f, "{}", n),
+            Color::Rgb(r, g, b) => return write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            Color::Black => "black",
+            Color::DarkGray => "dark_gray",
+            Color::Red => "red",
+            Color::LightRed => "light_red",
+            Color::Green => "green",
+            Color::LightGreen => "light_green",
+            Color::Yellow => "yellow",
+            Color::LightYellow => "light_yellow",
+            Color::Blue => "blue",
+            Color::LightBlue => "light_blue",
+            Color::Purple => "purple",
+            Color::LightPurple => "light_purple",
+            Color::Magenta => "magenta",
+            Color::LightMagenta => "light_magenta",
+            Color::Cyan => "cyan",
+            Color::LightCyan => "light_cyan",
+            Color::White => "white",
+            Color::LightGray => "light_gray",
+            Color::Default => "default",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ColorSpec, ParseColorError};
+    use crate::rgb::Rgb;
+    use crate::style::Color::{self, *};
+
+    fn parse(s: &str) -> Result<Color, ParseColorError> {
+        s.parse()
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(parse("red"), Ok(Red));
+        assert_eq!(parse("RED"), Ok(Red));
+        assert_eq!(parse("light_red"), Ok(LightRed));
+        assert_eq!(parse("bright-red"), Ok(LightRed));
+        assert_eq!(parse("BrightRed"), Ok(LightRed));
+        assert_eq!(parse("bright_black"), Ok(DarkGray));
+        assert_eq!(parse("dark_grey"), Ok(DarkGray));
+        assert_eq!(parse("bright_white"), Ok(LightGray));
+        assert_eq!(parse("light gray"), Ok(LightGray));
+        assert_eq!(parse("magenta"), Ok(Magenta));
+        assert_eq!(parse("default"), Ok(Default));
+        assert!(parse("bright_default").is_err());
+        assert!(parse("octarine").is_err());
+        #[cfg(not(feature = "named_colors"))]
+        {
+            assert!(parse("dark_red").is_err());
+            assert!(parse("gray").is_err());
+        }
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn numbers_and_hex() {
+        assert_eq!(parse("0"), Ok(Fixed(0)));
+        assert_eq!(parse(" 255 "), Ok(Fixed(255)));
+        assert_eq!(parse("#aBc"), Ok(Rgb(0xaa, 0xbb, 0xcc)));
+        assert_eq!(parse("0x123456"), Ok(Rgb(0x12, 0x34, 0x56)));
+        assert_eq!(parse("rgb(1,2, 3)"), Ok(Rgb(1, 2, 3)));
+        assert_eq!("0x123456".parse(), Ok(Rgb::new(0x12, 0x34, 0x56)));
+    }
+
+    #[test]
+    fn errors() {
+        let message = |s: &str| parse(s).unwrap_err().to_string();
+        assert_eq!(
+            message("256"),
+            "invalid color `256`: palette indices go from 0 to 255"
+        );
+        assert_eq!(
+            message("#12g"),
+            "invalid color `#12g`: expected only hex digits"
+        );
+        assert_eq!(
+            message("0xabc"),
+            "invalid color `0xabc`: hex colors need 3 or 6 digits"
+        );
+        assert_eq!(
+            message("rgb(1, 2)"),
+            "invalid color `rgb(1, 2)`: expected three numbers from 0 to 255 in `rgb()`"
+        );
+        assert_eq!(
+            message("rgb(1, 2, 300)"),
+            "invalid color `rgb(1, 2, 300)`: expected three numbers from 0 to 255 in `rgb()`"
+        );
+        assert!(message("octarine").starts_with("invalid color `octarine`: expected a color name"));
+        assert_eq!("octarine".parse::<Rgb>().unwrap_err().input(), "octarine");
+    }
+
+    #[test]
+    fn round_trip() {
+        let colors = [
+            Black,
+            DarkGray,
+            Red,
+            LightRed,
+            Green,
+            LightGreen,
+            Yellow,
+            LightYellow,
+            Blue,
+            LightBlue,
+            Purple,
+            LightPurple,
+            Magenta,
+            LightMagenta,
+            Cyan,
+            LightCyan,
+            White,
+            LightGray,
+            Default,
+            Fixed(17),
+            Rgb(1, 2, 3),
+        ];
+        for color in colors {
+            assert_eq!(parse(&ColorSpec(color).to_string()), Ok(color));
+        }
+    }
+}
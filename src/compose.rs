@@ -0,0 +1,223 @@
+use crate::attributes::Attributes;
+use crate::style::{Style, UnderlineStyle};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+impl Style {
+    /// Returns this style with `other` layered on top of it: every property
+    /// that `other` sets wins, and the rest are left as they are in this
+    /// style. Text attributes are combined, so bold text overlaid with
+    /// italic text is both.
+    ///
+    /// This is also available as the `|` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, Style};
+    ///
+    /// let row = Color::White.on(Color::Black);
+    /// let column = Color::Yellow.bold();
+    /// assert_eq!(row.overlay(&column), Color::Yellow.on(Color::Black).bold());
+    /// assert_eq!(row | column, row.overlay(&column));
+    /// ```
+    pub fn overlay(&self, other: &Style) -> Style {
+        Style {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+            underline_color: other.underline_color.or(self.underline_color),
+            attributes: self.attributes | other.attributes,
+            underline_style: if other.is_underline() {
+                other.underline_style
+            } else {
+                self.underline_style
+            },
+            prefix_with_reset: self.prefix_with_reset || other.prefix_with_reset,
+        }
+    }
+
+    /// Returns this style without any of the properties that `other` sets,
+    /// whatever their values in `other` are.
+    ///
+    /// This is also available as the `-` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, Style};
+    ///
+    /// let style = Color::Red.on(Color::Blue).bold().italic();
+    /// let plain = Style::new().fg(Color::Default).bold();
+    /// assert_eq!(style.without(&plain), Style::new().on(Color::Blue).italic());
+    /// assert_eq!(style - plain, style.without(&plain));
+    /// ```
+    pub fn without(&self, other: &Style) -> Style {
+        let attributes = self.attributes - other.attributes;
+        Style {
+            foreground: self.foreground.filter(|_| other.foreground.is_none()),
+            background: self.background.filter(|_| other.background.is_none()),
+            underline_color: self
+                .underline_color
+                .filter(|_| other.underline_color.is_none()),
+            attributes,
+            underline_style: if attributes.contains(Attributes::UNDERLINE) {
+                self.underline_style
+            } else {
+                UnderlineStyle::Single
+            },
+            prefix_with_reset: self.prefix_with_reset && !other.prefix_with_reset,
+        }
+    }
+
+    /// Returns a style with only the properties that this style and `other`
+    /// both have, with the same values.
+    ///
+    /// This is also available as the `&` operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, Style};
+    ///
+    /// let a = Color::Red.on(Color::Blue).bold();
+    /// let b = Color::Green.on(Color::Blue).bold().italic();
+    /// assert_eq!(a.intersection(&b), Style::new().on(Color::Blue).bold());
+    /// assert_eq!(a & b, a.intersection(&b));
+    /// ```
+    pub fn intersection(&self, other: &Style) -> Style {
+        let attributes = self.attributes & other.attributes;
+        let same_underline = self.underline_style == other.underline_style;
+        Style {
+            foreground: self
+                .foreground
+                .filter(|_| self.foreground == other.foreground),
+            background: self
+                .background
+                .filter(|_| self.background == other.background),
+            underline_color: self
+                .underline_color
+                .filter(|_| self.underline_color == other.underline_color),
+            attributes: if same_underline {
+                attributes
+            } else {
+                attributes - Attributes::UNDERLINE
+            },
+            underline_style: if same_underline {
+                self.underline_style
+            } else {
+                UnderlineStyle::Single
+            },
+            prefix_with_reset: self.prefix_with_reset && other.prefix_with_reset,
+        }
+    }
+}
+
+impl BitOr for Style {
+    type Output = Style;
+
+    /// Layers the right style on top of the left one. See [`Style::overlay`].
+    fn bitor(self, other: Style) -> Style {
+        self.overlay(&other)
+    }
+}
+
+impl BitOrAssign for Style {
+    fn bitor_assign(&mut self, other: Style) {
+        *self = self.overlay(&other)
+    }
+}
+
+impl BitAnd for Style {
+    type Output = Style;
+
+    /// Keeps what both styles have. See [`Style::intersection`].
+    fn bitand(self, other: Style) -> Style {
+        self.intersection(&other)
+    }
+}
+
+impl BitAndAssign for Style {
+    fn bitand_assign(&mut self, other: Style) {
+        *self = self.intersection(&other)
+    }
+}
+
+impl Sub for Style {
+    type Output = Style;
+
+    /// Removes what the right style sets from the left one. See
+    /// [`Style::without`].
+    fn sub(self, other: Style) -> Style {
+        self.without(&other)
+    }
+}
+
+impl SubAssign for Style {
+    fn sub_assign(&mut self, other: Style) {
+        *self = self.without(&other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::style::Color::*;
+    use crate::style::{Style, UnderlineStyle};
+
+    #[test]
+    fn overlay() {
+        let base = White.on(Black).italic();
+        assert_eq!(base | Style::new(), base);
+        assert_eq!(Style::new() | base, base);
+        assert_eq!(base | Red.bold(), Red.on(Black).italic().bold());
+        assert_eq!(
+            base | Style::new().on(Blue) | Style::new().underline(),
+            White.on(Blue).italic().underline()
+        );
+        assert_eq!(
+            Style::new().underline_style(UnderlineStyle::Curly) | Style::new().bold(),
+            Style::new().underline_style(UnderlineStyle::Curly).bold()
+        );
+        assert_eq!(
+            Style::new().underline_style(UnderlineStyle::Curly)
+                | Style::new().underline_style(UnderlineStyle::Dotted),
+            Style::new().underline_style(UnderlineStyle::Dotted)
+        );
+
+        let mut style = base;
+        style |= Style::new().underline_color(Red);
+        assert_eq!(style, base.underline_color(Red));
+    }
+
+    #[test]
+    fn without() {
+        let style = Red.on(Blue).bold().underline_style(UnderlineStyle::Dashed);
+        assert_eq!(style - Style::new(), style);
+        assert_eq!(style - style, Style::new());
+        assert_eq!(
+            style - Style::new().on(Red),
+            Red.bold().underline_style(UnderlineStyle::Dashed)
+        );
+        assert_eq!(style - Style::new().underline(), Red.on(Blue).bold());
+
+        let mut style = style;
+        style -= Style::new().bold().fg(Default);
+        assert_eq!(
+            style,
+            Style::new()
+                .on(Blue)
+                .underline_style(UnderlineStyle::Dashed)
+        );
+    }
+
+    #[test]
+    fn intersection() {
+        let a = Red.on(Blue).bold().underline();
+        let b = Green.on(Blue).bold().underline_style(UnderlineStyle::Curly);
+        assert_eq!(a & b, Style::new().on(Blue).bold());
+        assert_eq!(a & a, a);
+        assert_eq!(a & Style::new(), Style::new());
+
+        let mut c = a;
+        c &= Red.italic();
+        assert_eq!(c, Red.normal());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::style::Style;
+use crate::attributes::Attributes;
+use crate::style::{Style, UnderlineStyle};
 use std::fmt;
 
 /// Styles have a special `Debug` implementation that only shows the fields that
@@ -16,14 +17,22 @@ impl fmt::Debug for Style {
             fmt.debug_struct("Style")
                 .field("foreground", &self.foreground)
                 .field("background", &self.background)
-                .field("blink", &self.is_blink)
-                .field("bold", &self.is_bold)
-                .field("dimmed", &self.is_dimmed)
-                .field("hidden", &self.is_hidden)
-                .field("italic", &self.is_italic)
-                .field("reverse", &self.is_reverse)
-                .field("strikethrough", &self.is_strikethrough)
-                .field("underline", &self.is_underline)
+                .field("underline_color", &self.underline_color)
+                .field("blink", &self.is_blink())
+                .field("bold", &self.is_bold())
+                .field("dimmed", &self.is_dimmed())
+                .field("encircled", &self.is_encircled())
+                .field("framed", &self.is_framed())
+                .field("hidden", &self.is_hidden())
+                .field("italic", &self.is_italic())
+                .field("overline", &self.is_overline())
+                .field("rapid_blink", &self.is_rapid_blink())
+                .field("reverse", &self.is_reverse())
+                .field("strikethrough", &self.is_strikethrough())
+                .field("subscript", &self.is_subscript())
+                .field("superscript", &self.is_superscript())
+                .field("underline", &self.is_underline())
+                .field("underline_style", &self.underline_style)
                 .finish()
         } else if self.is_plain() {
             fmt.write_str("Style {}")
@@ -48,6 +57,14 @@ impl fmt::Debug for Style {
                 write!(fmt, "on({:?})", bg)?
             }
 
+            if let Some(ul) = self.underline_color {
+                if written_anything {
+                    fmt.write_str(", ")?
+                }
+                written_anything = true;
+                write!(fmt, "underline_color({:?})", ul)?
+            }
+
             {
                 let mut write_flag = |name| {
                     if written_anything {
@@ -57,29 +74,20 @@ impl fmt::Debug for Style {
                     fmt.write_str(name)
                 };
 
-                if self.is_blink {
-                    write_flag("blink")?
-                }
-                if self.is_bold {
-                    write_flag("bold")?
-                }
-                if self.is_dimmed {
-                    write_flag("dimmed")?
-                }
-                if self.is_hidden {
-                    write_flag("hidden")?
-                }
-                if self.is_italic {
-                    write_flag("italic")?
-                }
-                if self.is_reverse {
-                    write_flag("reverse")?
+                for (name, attribute) in Attributes::NAMED {
+                    if attribute != Attributes::UNDERLINE && self.attributes.contains(attribute) {
+                        write_flag(name)?
+                    }
                 }
-                if self.is_strikethrough {
-                    write_flag("strikethrough")?
+            }
+
+            if self.is_underline() {
+                if written_anything {
+                    fmt.write_str(", ")?
                 }
-                if self.is_underline {
-                    write_flag("underline")?
+                match self.underline_style {
+                    UnderlineStyle::Single => fmt.write_str("underline")?,
+                    kind => write!(fmt, "underline({:?})", kind)?,
                 }
             }
 
@@ -90,8 +98,9 @@ impl fmt::Debug for Style {
 
 #[cfg(test)]
 mod test {
+    use crate::attributes::Attributes;
     use crate::style::Color::*;
-    use crate::style::Style;
+    use crate::style::{Style, UnderlineStyle};
 
     macro_rules! test {
         ($name: ident: $obj: expr => $result: expr) => {
@@ -109,6 +118,10 @@ mod test {
 
     test!(red:     Red.normal()                     => "Style { fg(Red) }");
     test!(redblue: Red.normal().on(Rgb(3, 2, 4))    => "Style { fg(Red), on(Rgb(3, 2, 4)) }");
+    test!(ulcolor: Red.underline().underline_color(Fixed(3)) => "Style { fg(Red), underline_color(Fixed(3)), underline }");
+    test!(others:  Style::new().superscript().overline().framed() => "Style { framed, overline, superscript }");
+    test!(curly:   Red.underline_style(UnderlineStyle::Curly) => "Style { fg(Red), underline(Curly) }");
+    test!(stale:   Style { attributes: Attributes::empty(), ..Style::new().underline_style(UnderlineStyle::Curly) } => "Style {}");
 
     test!(everything:
             Red.on(Blue).blink().bold().dimmed().hidden().italic().reverse().strikethrough().underline() =>
@@ -122,14 +135,22 @@ mod test {
         Blue,
     ),
     background: None,
+    underline_color: None,
     blink: false,
     bold: true,
     dimmed: false,
+    encircled: false,
+    framed: false,
     hidden: false,
     italic: false,
+    overline: false,
+    rapid_blink: false,
     reverse: false,
     strikethrough: false,
+    subscript: false,
+    superscript: false,
     underline: false,
+    underline_style: Single,
 }";
 
         let style = Blue.bold();
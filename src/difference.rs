@@ -1,4 +1,7 @@
 use super::Style;
+use crate::ansi::OFF_CODES;
+use crate::attributes::Attributes;
+use crate::terminal::Transitions;
 
 /// When printing out one colored string followed by another, use one of
 /// these rules to figure out which *extra* control codes need to be sent.
@@ -12,6 +15,11 @@ pub enum Difference {
     /// command and then the second string's styles.
     Reset,
 
+    /// Turn off the styles in `removed` using the codes that do only that,
+    /// then print out the control codes for `added`. This is only used for
+    /// `Transitions::Minimal`.
+    Transition { removed: Style, added: Style },
+
     /// The before style is exactly the same as the after style, so no further
     /// control codes need to be printed.
     Empty,
@@ -43,93 +51,126 @@ impl Difference {
             return Empty;
         }
 
-        // Cannot un-bold, so must Reset.
-        if first.is_bold && !next.is_bold {
+        // Cannot turn off a text attribute, so must Reset.
+        if !(first.attributes - next.attributes).is_empty() {
             return Reset;
         }
 
-        if first.is_dimmed && !next.is_dimmed {
+        // Cannot go from foreground to no foreground, so must Reset.
+        if first.foreground.is_some() && next.foreground.is_none() {
             return Reset;
         }
 
-        if first.is_italic && !next.is_italic {
+        // Cannot go from background to no background, so must Reset.
+        if first.background.is_some() && next.background.is_none() {
             return Reset;
         }
 
-        // Cannot un-underline, so must Reset.
-        if first.is_underline && !next.is_underline {
+        if first.underline_color.is_some() && next.underline_color.is_none() {
             return Reset;
         }
 
-        if first.is_blink && !next.is_blink {
-            return Reset;
-        }
+        let mut extra_styles = Style {
+            attributes: next.attributes - first.attributes,
+            ..Style::default()
+        };
 
-        if first.is_reverse && !next.is_reverse {
-            return Reset;
+        if next.is_underline() && first.underline_style != next.underline_style {
+            extra_styles.attributes |= Attributes::UNDERLINE;
         }
-
-        if first.is_hidden && !next.is_hidden {
-            return Reset;
+        if extra_styles.is_underline() {
+            extra_styles.underline_style = next.underline_style;
         }
 
-        if first.is_strikethrough && !next.is_strikethrough {
-            return Reset;
+        if first.foreground != next.foreground {
+            extra_styles.foreground = next.foreground;
         }
 
-        // Cannot go from foreground to no foreground, so must Reset.
-        if first.foreground.is_some() && next.foreground.is_none() {
-            return Reset;
+        if first.background != next.background {
+            extra_styles.background = next.background;
         }
 
-        // Cannot go from background to no background, so must Reset.
-        if first.background.is_some() && next.background.is_none() {
-            return Reset;
+        if first.underline_color != next.underline_color {
+            extra_styles.underline_color = next.underline_color;
         }
 
-        let mut extra_styles = Style::default();
-
-        if first.is_bold != next.is_bold {
-            extra_styles.is_bold = true;
-        }
+        ExtraStyles(extra_styles)
+    }
 
-        if first.is_dimmed != next.is_dimmed {
-            extra_styles.is_dimmed = true;
+    /// Compute the style difference between two styles in the way that
+    /// the given transitions call for.
+    pub fn between_with(first: &Style, next: &Style, transitions: Transitions) -> Difference {
+        match transitions {
+            Transitions::Reset => Difference::between(first, next),
+            Transitions::Minimal => Difference::minimal(first, next),
         }
+    }
 
-        if first.is_italic != next.is_italic {
-            extra_styles.is_italic = true;
-        }
+    /// Compute the style difference between two styles, turning off any
+    /// styles that the second one doesn't have with the codes for that
+    /// (such as 22 for bold and dimmed text, or 39 for the foreground color)
+    /// instead of a reset.
+    pub fn minimal(first: &Style, next: &Style) -> Difference {
+        use self::Difference::*;
 
-        if first.is_underline != next.is_underline {
-            extra_styles.is_underline = true;
+        if first == next {
+            return Empty;
         }
 
-        if first.is_blink != next.is_blink {
-            extra_styles.is_blink = true;
-        }
+        let mut removed = Style {
+            attributes: first.attributes - next.attributes,
+            ..Style::default()
+        };
+        let mut added = Style {
+            attributes: next.attributes - first.attributes,
+            ..Style::default()
+        };
 
-        if first.is_reverse != next.is_reverse {
-            extra_styles.is_reverse = true;
+        // Some codes turn off more than one attribute, so the ones that stay
+        // have to be added back.
+        for (attributes, _) in OFF_CODES {
+            if removed.attributes.intersects(attributes) {
+                added.attributes |= next.attributes & attributes;
+            }
         }
 
-        if first.is_hidden != next.is_hidden {
-            extra_styles.is_hidden = true;
+        // Changing the kind of underline only needs the new kind.
+        if first.is_underline()
+            && next.is_underline()
+            && first.underline_style != next.underline_style
+        {
+            added.attributes |= Attributes::UNDERLINE;
         }
-
-        if first.is_strikethrough != next.is_strikethrough {
-            extra_styles.is_strikethrough = true;
+        if added.is_underline() {
+            added.underline_style = next.underline_style;
         }
 
         if first.foreground != next.foreground {
-            extra_styles.foreground = next.foreground;
+            match next.foreground {
+                None => removed.foreground = first.foreground,
+                color => added.foreground = color,
+            }
         }
 
         if first.background != next.background {
-            extra_styles.background = next.background;
+            match next.background {
+                None => removed.background = first.background,
+                color => added.background = color,
+            }
         }
 
-        ExtraStyles(extra_styles)
+        if first.underline_color != next.underline_color {
+            match next.underline_color {
+                None => removed.underline_color = first.underline_color,
+                color => added.underline_color = color,
+            }
+        }
+
+        if removed.is_plain() {
+            ExtraStyles(added)
+        } else {
+            Transition { removed, added }
+        }
     }
 }
 
@@ -138,7 +179,7 @@ mod test {
     use super::Difference::*;
     use super::*;
     use crate::style::Color::*;
-    use crate::style::Style;
+    use crate::style::{Style, UnderlineStyle};
 
     fn style() -> Style {
         Style::new()
@@ -171,4 +212,36 @@ mod test {
     test!(removal_of_hidden:          style().hidden();        style()  => Reset);
     test!(removal_of_dimmed:          style().dimmed();        style()  => Reset);
     test!(removal_of_blink:           style().blink();         style()  => Reset);
+    test!(removal_of_overline:        style().overline();      style()  => Reset);
+    test!(removal_of_framed:          style().framed();        style()  => Reset);
+    test!(addition_of_encircled:      style(); style().encircled()      => ExtraStyles(style().encircled()));
+    test!(addition_of_superscript:    style(); style().superscript()    => ExtraStyles(style().superscript()));
+
+    test!(underline_change:   style().underline(); style().underline_style(UnderlineStyle::Curly) => ExtraStyles(style().underline_style(UnderlineStyle::Curly)));
+    test!(underline_color_change:  style().underline_color(Red); style().underline_color(Blue) => ExtraStyles(style().underline_color(Blue)));
+    test!(removal_of_underline_color: style().underline_color(Red); style() => Reset);
+    test!(underline_change_2: style().underline_style(UnderlineStyle::Dotted); style().underline() => ExtraStyles(style().underline()));
+
+    macro_rules! minimal {
+        ($name: ident: $first: expr; $next: expr => $result: expr) => {
+            #[test]
+            fn $name() {
+                assert_eq!($result, Difference::minimal(&$first, &$next));
+            }
+        };
+    }
+
+    minimal!(minimal_nothing:      Green.bold(); Green.bold()   => Empty);
+    minimal!(minimal_addition:     Green.normal(); Green.bold() => ExtraStyles(style().bold()));
+    minimal!(minimal_color_change: Red.bold(); Blue.bold()      => ExtraStyles(Blue.normal()));
+    minimal!(minimal_unbold:       Red.bold(); Red.normal()     => Transition { removed: style().bold(), added: style() });
+    minimal!(minimal_undim:        style().bold().dimmed(); style().bold() => Transition { removed: style().dimmed(), added: style().bold() });
+    minimal!(minimal_no_color:     Red.on(Blue); style()        => Transition { removed: Red.on(Blue), added: style() });
+    minimal!(minimal_underline:    style().underline(); style().underline_style(UnderlineStyle::Double) => ExtraStyles(style().underline_style(UnderlineStyle::Double)));
+    minimal!(minimal_underline_color: Red.normal().underline_color(Blue); Red.normal() => Transition { removed: style().underline_color(Blue), added: style() });
+    minimal!(minimal_overline:     style().overline().bold(); style().bold() => Transition { removed: style().overline(), added: style() });
+    minimal!(minimal_unblink:      style().blink().rapid_blink(); style().rapid_blink() => Transition { removed: style().blink(), added: style().rapid_blink() });
+    minimal!(minimal_unframe:      style().framed(); style().encircled() => Transition { removed: style().framed(), added: style().encircled() });
+    minimal!(minimal_subscript:    style().superscript(); style().subscript() => Transition { removed: style().superscript(), added: style().subscript() });
+    minimal!(minimal_both:         Red.italic(); Blue.underline() => Transition { removed: style().italic(), added: Blue.underline() });
 }
@@ -1,13 +1,14 @@
-use crate::ansi::RESET;
+use crate::ansi::{write_transition, RESET};
 use crate::difference::Difference;
 use crate::style::{Color, Style};
+use crate::terminal::Capabilities;
 use crate::write::AnyWrite;
 use std::borrow::Cow;
 use std::fmt;
 use std::io;
 
 #[derive(Eq, PartialEq, Debug)]
-enum OSControl<'a, S: 'a + ToOwned + ?Sized>
+pub(crate) enum OSControl<'a, S: 'a + ToOwned + ?Sized>
 where
     <S as ToOwned>::Owned: fmt::Debug,
 {
@@ -37,7 +38,7 @@ where
 {
     pub(crate) style: Style,
     pub(crate) string: Cow<'a, S>,
-    oscontrol: Option<OSControl<'a, S>>,
+    pub(crate) oscontrol: Option<OSControl<'a, S>>,
 }
 
 /// Cloning an `AnsiGenericString` will clone its underlying string.
@@ -278,7 +279,7 @@ impl Color {
 impl<'a> fmt::Display for AnsiString<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let w: &mut dyn fmt::Write = f;
-        self.write_to_any(w)
+        self.write_to_any(w, Capabilities::global())
     }
 }
 
@@ -287,7 +288,7 @@ impl<'a> AnsiByteString<'a> {
     /// sequences for the associated `Style` around the bytes.
     pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
         let w: &mut dyn io::Write = w;
-        self.write_to_any(w)
+        self.write_to_any(w, Capabilities::global())
     }
 }
 
@@ -297,8 +298,16 @@ where
     &'a S: AsRef<[u8]>,
 {
     // write the part within the styling prefix and suffix
-    fn write_inner<W: AnyWrite<Wstr = S> + ?Sized>(&self, w: &mut W) -> Result<(), W::Error> {
+    fn write_inner<W: AnyWrite<Wstr = S> + ?Sized>(
+        &self,
+        w: &mut W,
+        caps: Capabilities,
+    ) -> Result<(), W::Error> {
+        // Without the escape sequences, only the text of a link is left,
+        // and a title disappears completely.
         match &self.oscontrol {
+            Some(OSControl::Link { .. }) if !caps.hyperlinks => w.write_str(self.string.as_ref()),
+            Some(OSControl::Title) if !caps.titles => Ok(()),
             Some(OSControl::Link { url: u }) => {
                 write!(w, "\x1B]8;;")?;
                 w.write_str(u.as_ref())?;
@@ -315,10 +324,15 @@ where
         }
     }
 
-    fn write_to_any<W: AnyWrite<Wstr = S> + ?Sized>(&self, w: &mut W) -> Result<(), W::Error> {
-        write!(w, "{}", self.style.prefix())?;
-        self.write_inner(w)?;
-        write!(w, "{}", self.style.suffix())
+    pub(crate) fn write_to_any<W: AnyWrite<Wstr = S> + ?Sized>(
+        &self,
+        w: &mut W,
+        caps: Capabilities,
+    ) -> Result<(), W::Error> {
+        let style = self.style.to_capabilities(caps);
+        style.write_prefix(w)?;
+        self.write_inner(w, caps)?;
+        style.write_suffix(w)
     }
 }
 
@@ -327,7 +341,7 @@ where
 impl<'a> fmt::Display for AnsiStrings<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let f: &mut dyn fmt::Write = f;
-        self.write_to_any(f)
+        self.write_to_any(f, Capabilities::global())
     }
 }
 
@@ -337,7 +351,7 @@ impl<'a> AnsiByteStrings<'a> {
     /// bytes.
     pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
         let w: &mut dyn io::Write = w;
-        self.write_to_any(w)
+        self.write_to_any(w, Capabilities::global())
     }
 }
 
@@ -346,7 +360,11 @@ where
     <S as ToOwned>::Owned: fmt::Debug,
     &'a S: AsRef<[u8]>,
 {
-    fn write_to_any<W: AnyWrite<Wstr = S> + ?Sized>(&self, w: &mut W) -> Result<(), W::Error> {
+    pub(crate) fn write_to_any<W: AnyWrite<Wstr = S> + ?Sized>(
+        &self,
+        w: &mut W,
+        caps: Capabilities,
+    ) -> Result<(), W::Error> {
         use self::Difference::*;
 
         let first = match self.0.first() {
@@ -354,24 +372,32 @@ where
             Some(f) => f,
         };
 
-        write!(w, "{}", first.style.prefix())?;
-        first.write_inner(w)?;
+        first.style.to_capabilities(caps).write_prefix(w)?;
+        first.write_inner(w, caps)?;
 
         for window in self.0.windows(2) {
-            match Difference::between(&window[0].style, &window[1].style) {
-                ExtraStyles(style) => write!(w, "{}", style.prefix())?,
-                Reset => write!(w, "{}{}", RESET, window[1].style.prefix())?,
+            let (this, next) = (
+                window[0].style.to_capabilities(caps),
+                window[1].style.to_capabilities(caps),
+            );
+            match Difference::between_with(&this, &next, caps.transitions) {
+                ExtraStyles(style) => style.write_prefix(w)?,
+                Reset => {
+                    write!(w, "{}", RESET)?;
+                    next.write_prefix(w)?;
+                }
+                Transition { removed, added } => write_transition(&removed, &added, w)?,
                 Empty => { /* Do nothing! */ }
             }
 
-            window[1].write_inner(w)?;
+            window[1].write_inner(w, caps)?;
         }
 
         // Write the final reset string after all of the AnsiStrings have been
         // written, *except* if the last one has no styles, because it would
         // have already been written by this point.
         if let Some(last) = self.0.last() {
-            if !last.style.is_plain() {
+            if !last.style.to_capabilities(caps).is_plain() {
                 write!(w, "{}", RESET)?;
             }
         }
@@ -426,7 +452,7 @@ mod tests {
 
         // does not introduce spurious SGR codes (reset or otherwise) adjacent
         // to plain strings
-        let joined = AnsiStrings(&[unstyled.clone()]).to_string();
+        let joined = AnsiStrings(std::slice::from_ref(&unstyled)).to_string();
         assert!(
             !joined.contains("\x1B["),
             "{:?} does contain \\x1B[",
@@ -480,7 +506,7 @@ mod tests {
         let after = Green.paint(" After link.");
 
         // Assemble with link by itself
-        let joined = AnsiStrings(&[link.clone()]).to_string();
+        let joined = AnsiStrings(std::slice::from_ref(&link)).to_string();
         #[cfg(feature = "gnu_legacy")]
         assert_eq!(joined, format!("\x1B[04;34m\x1B]8;;https://example.com\x1B\\Link to example.com.\x1B]8;;\x1B\\\x1B[0m"));
         #[cfg(not(feature = "gnu_legacy"))]
@@ -0,0 +1,198 @@
+use crate::parse::basic_color;
+use crate::rgb::Rgb;
+use crate::style::Color;
+
+/// The colors xterm uses by default for the first sixteen palette entries.
+/// Most terminals let the user change these, so they are only a guess at
+/// what the colors will really look like.
+pub(crate) const XTERM_16: [Rgb; 16] = [
+    Rgb::new(0x00, 0x00, 0x00),
+    Rgb::new(0xcd, 0x00, 0x00),
+    Rgb::new(0x00, 0xcd, 0x00),
+    Rgb::new(0xcd, 0xcd, 0x00),
+    Rgb::new(0x00, 0x00, 0xee),
+    Rgb::new(0xcd, 0x00, 0xcd),
+    Rgb::new(0x00, 0xcd, 0xcd),
+    Rgb::new(0xe5, 0xe5, 0xe5),
+    Rgb::new(0x7f, 0x7f, 0x7f),
+    Rgb::new(0xff, 0x00, 0x00),
+    Rgb::new(0x00, 0xff, 0x00),
+    Rgb::new(0xff, 0xff, 0x00),
+    Rgb::new(0x5c, 0x5c, 0xff),
+    Rgb::new(0xff, 0x00, 0xff),
+    Rgb::new(0x00, 0xff, 0xff),
+    Rgb::new(0xff, 0xff, 0xff),
+];
+
+/// The intensities each channel can take in the 6×6×6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Return the color of an entry in the xterm 256-color palette.
+pub(crate) fn fixed_to_rgb(num: u8) -> Rgb {
+    match num {
+        0..=15 => XTERM_16[num as usize],
+        16..=231 => {
+            let n = num - 16;
+            Rgb::new(
+                CUBE_LEVELS[(n / 36) as usize],
+                CUBE_LEVELS[(n / 6 % 6) as usize],
+                CUBE_LEVELS[(n % 6) as usize],
+            )
+        }
+        232..=255 => Rgb::gray(8 + 10 * (num - 232)),
+    }
+}
+
+/// An approximation of how different two colors look, using the “redmean”
+/// weighting, which gives the green channel the most weight and shifts the
+/// weight of red and blue depending on how red the colors are.
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    let red_mean = (u32::from(a.r) + u32::from(b.r)) / 2;
+    let dr = u32::from(a.r.abs_diff(b.r));
+    let dg = u32::from(a.g.abs_diff(b.g));
+    let db = u32::from(a.b.abs_diff(b.b));
+    (((512 + red_mean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - red_mean) * db * db) >> 8)
+}
+
+/// Return the index of the cube level closest to the given intensity.
+fn nearest_cube_level(value: u8) -> u8 {
+    match value {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (value - 35) / 40,
+    }
+}
+
+/// Return the entry of the 256-color palette, outside of the first sixteen,
+/// that looks closest to the given color: either the closest point of the
+/// color cube, or the closest shade of the grayscale ramp.
+fn rgb_to_fixed(rgb: Rgb) -> u8 {
+    let (r, g, b) = (
+        nearest_cube_level(rgb.r),
+        nearest_cube_level(rgb.g),
+        nearest_cube_level(rgb.b),
+    );
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    let average = (u32::from(rgb.r) + u32::from(rgb.g) + u32::from(rgb.b)) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    if distance(rgb, fixed_to_rgb(gray)) < distance(rgb, fixed_to_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Return the one of the sixteen basic colors that looks closest to the given
+/// color, as it would appear with xterm’s default colors.
+fn rgb_to_basic(rgb: Rgb) -> Color {
+    let index = (0..16)
+        .min_by_key(|&i| distance(rgb, XTERM_16[i]))
+        .unwrap_or_default();
+    basic_color(index as u32 % 8, index >= 8)
+}
+
+impl Color {
+    /// Returns a color that can be displayed on terminals with 256 colors.
+    ///
+    /// `Rgb` colors are mapped onto the closest color in the 6×6×6 color cube
+    /// or the grayscale ramp of the xterm palette, as a `Fixed` color. Every
+    /// other color can already be displayed, so it is returned as it is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// assert_eq!(Color::Rgb(70, 130, 180).to_ansi256(), Color::Fixed(67));
+    /// assert_eq!(Color::Rgb(128, 128, 128).to_ansi256(), Color::Fixed(244));
+    /// assert_eq!(Color::Red.to_ansi256(), Color::Red);
+    /// ```
+    pub fn to_ansi256(self) -> Color {
+        match self {
+            Color::Rgb(r, g, b) => Color::Fixed(rgb_to_fixed(Rgb::new(r, g, b))),
+            _ => self,
+        }
+    }
+
+    /// Returns a color that can be displayed on terminals with only the
+    /// sixteen basic colors.
+    ///
+    /// The first sixteen `Fixed` colors are turned into the matching named
+    /// colors. The rest of the `Fixed` colors, and `Rgb` colors, are mapped
+    /// onto whichever named color looks closest to them with xterm’s default
+    /// colors. Named colors and `Default` are returned as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// assert_eq!(Color::Rgb(250, 10, 20).to_ansi16(), Color::LightRed);
+    /// assert_eq!(Color::Fixed(4).to_ansi16(), Color::Blue);
+    /// assert_eq!(Color::Fixed(28).to_ansi16(), Color::Green);
+    /// ```
+    pub fn to_ansi16(self) -> Color {
+        match self {
+            Color::Fixed(num @ 0..=15) => basic_color(u32::from(num % 8), num >= 8),
+            Color::Fixed(num) => rgb_to_basic(fixed_to_rgb(num)),
+            Color::Rgb(r, g, b) => rgb_to_basic(Rgb::new(r, g, b)),
+            _ => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::fixed_to_rgb;
+    use crate::rgb;
+    use crate::style::Color::*;
+
+    #[test]
+    fn palette() {
+        assert_eq!(fixed_to_rgb(9), rgb::Rgb::new(255, 0, 0));
+        assert_eq!(fixed_to_rgb(16), rgb::Rgb::new(0, 0, 0));
+        assert_eq!(fixed_to_rgb(67), rgb::Rgb::new(95, 135, 175));
+        assert_eq!(fixed_to_rgb(231), rgb::Rgb::new(255, 255, 255));
+        assert_eq!(fixed_to_rgb(232), rgb::Rgb::gray(8));
+        assert_eq!(fixed_to_rgb(255), rgb::Rgb::gray(238));
+    }
+
+    #[test]
+    fn cube_colors_map_to_themselves() {
+        for num in 16..=255 {
+            let rgb = fixed_to_rgb(num);
+            assert_eq!(
+                Rgb(rgb.r, rgb.g, rgb.b).to_ansi256(),
+                Fixed(num),
+                "{:?}",
+                rgb
+            );
+        }
+    }
+
+    #[test]
+    fn to_ansi256() {
+        assert_eq!(Rgb(0, 0, 0).to_ansi256(), Fixed(16));
+        assert_eq!(Rgb(255, 255, 255).to_ansi256(), Fixed(231));
+        assert_eq!(Rgb(255, 135, 0).to_ansi256(), Fixed(208));
+        assert_eq!(Rgb(30, 30, 30).to_ansi256(), Fixed(234));
+        assert_eq!(Rgb(250, 250, 250).to_ansi256(), Fixed(231));
+        assert_eq!(Fixed(3).to_ansi256(), Fixed(3));
+        assert_eq!(Default.to_ansi256(), Default);
+    }
+
+    #[test]
+    fn to_ansi16() {
+        assert_eq!(Rgb(0, 0, 0).to_ansi16(), Black);
+        assert_eq!(Rgb(255, 255, 255).to_ansi16(), LightGray);
+        assert_eq!(Rgb(200, 0, 0).to_ansi16(), Red);
+        assert_eq!(Rgb(100, 100, 255).to_ansi16(), LightBlue);
+        assert_eq!(Rgb(128, 128, 128).to_ansi16(), DarkGray);
+        assert_eq!(Fixed(13).to_ansi16(), LightPurple);
+        assert_eq!(Fixed(231).to_ansi16(), LightGray);
+        assert_eq!(Magenta.to_ansi16(), Magenta);
+        assert_eq!(Default.to_ansi16(), Default);
+    }
+}
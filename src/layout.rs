@@ -0,0 +1,598 @@
+use crate::display::{AnsiString, AnsiStrings, OSControl};
+use crate::style::Style;
+use crate::util::{fragment, grapheme_width, sub_string_columns, unstyled_width};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Which part of a string [`truncate`] removes to make it fit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Truncation {
+    /// Keep the start of the string, and put the ellipsis at the end.
+    End,
+
+    /// Keep the end of the string, and put the ellipsis at the start.
+    Start,
+
+    /// Keep both ends of the string, and put the ellipsis in the middle.
+    Middle,
+}
+
+/// Where [`pad`] puts text within the space it fills.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Put the text on the left, and the padding on the right.
+    Left,
+
+    /// Put the text on the right, and the padding on the left.
+    Right,
+
+    /// Put the text in the middle, with padding on both sides. When the
+    /// padding can’t be split evenly, the extra column goes on the right.
+    Center,
+}
+
+/// Return the style of the text at the given column, or the default style if
+/// there is no text there.
+fn style_at(column: usize, strs: &AnsiStrings) -> Style {
+    let mut pos = 0;
+    for i in strs.0.iter() {
+        if matches!(i.oscontrol, Some(OSControl::Title)) {
+            continue;
+        }
+        pos += i.width();
+        if column < pos {
+            return i.style;
+        }
+    }
+    Style::default()
+}
+
+/// Copy the strings that are displayed as text, as they are, leaving out
+/// titles.
+fn owned_text(strs: &AnsiStrings) -> Vec<AnsiString<'static>> {
+    strs.0
+        .iter()
+        .filter(|i| !matches!(i.oscontrol, Some(OSControl::Title)))
+        .map(|i| fragment(i, &i.string))
+        .collect()
+}
+
+/// Shorten the given AnsiStrings sequence so that it takes up no more than
+/// `max_width` terminal columns, replacing the removed text with `ellipsis`
+/// while keeping the formatting of the rest.
+///
+/// If `ellipsis` has a style, it is displayed with that style. If it is
+/// plain, it takes on the style of the text it replaces, so that it blends
+/// in with the text around it. Text that already fits is returned as it is,
+/// without an ellipsis. If not even the ellipsis fits, it gets cut down to
+/// `max_width`.
+///
+/// Wide characters are never split, so the result may be a column narrower
+/// than `max_width`. Titles are left out of the result. Like any other list
+/// of `AnsiString`s, the result ends with a reset when displayed as
+/// [`AnsiStrings`].
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{truncate, AnsiString, AnsiStrings, Truncation};
+/// use nu_ansi_term::Color::{Blue, Red};
+///
+/// let strings = [Red.paint("nu-ansi-"), Blue.paint("term")];
+/// let ellipsis = AnsiString::from("…");
+///
+/// let cut = truncate(&AnsiStrings(&strings), 6, &ellipsis, Truncation::End);
+/// assert_eq!(cut, [Red.paint("nu-an"), Red.paint("…")]);
+///
+/// let cut = truncate(&AnsiStrings(&strings), 6, &ellipsis, Truncation::Start);
+/// assert_eq!(cut, [Red.paint("…"), Red.paint("-"), Blue.paint("term")]);
+/// ```
+pub fn truncate(
+    strs: &AnsiStrings,
+    max_width: usize,
+    ellipsis: &AnsiString,
+    truncation: Truncation,
+) -> Vec<AnsiString<'static>> {
+    let width = unstyled_width(strs);
+    if width <= max_width {
+        return owned_text(strs);
+    }
+
+    let ellipsis_width = ellipsis.width();
+    if ellipsis_width > max_width {
+        return sub_string_columns(0, max_width, &AnsiStrings(std::slice::from_ref(ellipsis)));
+    }
+
+    let room = max_width - ellipsis_width;
+    let (head, tail, cut_at) = match truncation {
+        Truncation::End => (room, 0, room),
+        Truncation::Start => (0, room, width - room - 1),
+        Truncation::Middle => ((room + 1) / 2, room / 2, (room + 1) / 2),
+    };
+
+    let ellipsis_style = if ellipsis.style.is_plain() {
+        style_at(cut_at, strs)
+    } else {
+        ellipsis.style
+    };
+
+    let mut vec = sub_string_columns(0, head, strs);
+    vec.push(ellipsis_style.paint(ellipsis.as_str().to_owned()));
+    vec.extend(sub_string_columns(width - tail, tail, strs));
+    vec
+}
+
+/// Repeat the text of `fill` to make up `width` columns, in the style of
+/// `fill`. Any columns left over that are too narrow for the next grapheme
+/// cluster of `fill` are made up with spaces.
+fn fill_to(width: usize, fill: &AnsiString) -> Option<AnsiString<'static>> {
+    if width == 0 {
+        return None;
+    }
+
+    let mut text = String::new();
+    let mut text_width = 0;
+    let graphemes = fill
+        .as_str()
+        .graphemes(true)
+        .filter(|g| grapheme_width(g) > 0)
+        .cycle();
+    for grapheme in graphemes {
+        if text_width + grapheme_width(grapheme) > width {
+            break;
+        }
+        text.push_str(grapheme);
+        text_width += grapheme_width(grapheme);
+    }
+    text.extend(std::iter::repeat(' ').take(width - text_width));
+
+    Some(fill.style.paint(text))
+}
+
+/// Pad the given AnsiStrings sequence out to `width` terminal columns, while
+/// keeping the formatting.
+///
+/// The padding is made by repeating the text of `fill`, displayed in the style
+/// of `fill`, so it can be plain spaces, or something like a coloured bar
+/// made of spaces with a background color. Text that is already at least
+/// `width` columns wide is returned as it is. Titles are left out of the
+/// result.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{pad, Alignment, AnsiString, AnsiStrings};
+/// use nu_ansi_term::Color::{Blue, Red};
+///
+/// let strings = [Red.paint("日本")];
+///
+/// let padded = pad(&AnsiStrings(&strings), 7, Alignment::Right, &AnsiString::from(" "));
+/// assert_eq!(padded, [AnsiString::from("   "), Red.paint("日本")]);
+///
+/// let bar = Blue.reverse().paint(" ");
+/// let padded = pad(&AnsiStrings(&strings), 7, Alignment::Center, &bar);
+/// assert_eq!(padded, [Blue.reverse().paint(" "), Red.paint("日本"), Blue.reverse().paint("  ")]);
+/// ```
+pub fn pad(
+    strs: &AnsiStrings,
+    width: usize,
+    alignment: Alignment,
+    fill: &AnsiString,
+) -> Vec<AnsiString<'static>> {
+    let text_width = unstyled_width(strs);
+    let padding = width.saturating_sub(text_width);
+    let (left, right) = match alignment {
+        Alignment::Left => (0, padding),
+        Alignment::Right => (padding, 0),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+    };
+
+    let mut vec = Vec::new();
+    vec.extend(fill_to(left, fill));
+    vec.extend(owned_text(strs));
+    vec.extend(fill_to(right, fill));
+    vec
+}
+
+/// One grapheme cluster of the text being wrapped, as its position in the
+/// list of strings, and how many columns it takes up.
+struct Piece {
+    index: usize,
+    range: Range<usize>,
+    width: usize,
+}
+
+/// Whether a grapheme cluster is a place where a line may be broken. Spaces
+/// that are meant to keep words together are not.
+fn is_break(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .all(|c| c.is_whitespace() && !matches!(c, '\u{A0}' | '\u{2007}' | '\u{202F}'))
+}
+
+/// Turn the pieces of one line back into strings, merging neighbouring
+/// pieces that came from the same string.
+fn line_strings(strs: &AnsiStrings, pieces: &[Piece]) -> Vec<AnsiString<'static>> {
+    let mut vec = Vec::new();
+    let mut current: Option<(usize, Range<usize>)> = None;
+
+    for piece in pieces {
+        current = match current {
+            Some((index, range)) if index == piece.index && range.end == piece.range.start => {
+                Some((index, range.start..piece.range.end))
+            }
+            Some((index, range)) => {
+                vec.push(fragment(&strs.0[index], &strs.0[index].string[range]));
+                Some((piece.index, piece.range.clone()))
+            }
+            None => Some((piece.index, piece.range.clone())),
+        };
+    }
+    if let Some((index, range)) = current {
+        vec.push(fragment(&strs.0[index], &strs.0[index].string[range]));
+    }
+
+    vec
+}
+
+/// The lines of text built up by [`wrap`].
+struct Lines {
+    width: usize,
+    done: Vec<Vec<Piece>>,
+    line: Vec<Piece>,
+    line_width: usize,
+}
+
+impl Lines {
+    fn break_line(&mut self) {
+        self.done.push(std::mem::take(&mut self.line));
+        self.line_width = 0;
+    }
+
+    fn push(&mut self, piece: Piece) {
+        self.line_width += piece.width;
+        self.line.push(piece);
+    }
+
+    /// Add a word to the current line along with the spaces before it, or
+    /// start a new line with it if it doesn’t fit. Words too long to fit on
+    /// a line of their own get broken up.
+    fn push_word(&mut self, spaces: Vec<Piece>, word: Vec<Piece>) {
+        let spaces_width: usize = spaces.iter().map(|p| p.width).sum();
+        let word_width: usize = word.iter().map(|p| p.width).sum();
+
+        if self.line_width + spaces_width + word_width <= self.width {
+            self.line.extend(spaces);
+            self.line.extend(word);
+            self.line_width += spaces_width + word_width;
+            return;
+        }
+
+        if !self.line.is_empty() {
+            self.break_line();
+        }
+        for piece in word {
+            if self.line_width + piece.width > self.width && !self.line.is_empty() {
+                self.break_line();
+            }
+            self.push(piece);
+        }
+    }
+}
+
+/// Wrap the given AnsiStrings sequence into lines no wider than `width`
+/// terminal columns, while keeping the formatting.
+///
+/// Lines are broken between words where possible, dropping the spaces at the
+/// break. Words too long to fit on a line are broken between grapheme
+/// clusters instead. Newlines in the text always start a new line, and are
+/// not included in the result.
+///
+/// Each line is returned as its own list of `AnsiString`s, carrying the
+/// styles and hyperlinks of the text it came from, so that when each line is
+/// displayed as [`AnsiStrings`] it starts with the codes for its style,
+/// re-opens any hyperlink it is part of, and ends with a reset. This keeps
+/// the style of one line from bleeding into the next, which matters in
+/// pagers such as `less -R` that display lines independently.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{wrap, AnsiStrings, Color::{Blue, Red}};
+///
+/// let strings = [Red.paint("the quick "), Blue.paint("brown fox")];
+/// let lines = wrap(&AnsiStrings(&strings), 12);
+/// assert_eq!(lines, [
+///     vec![Red.paint("the quick")],
+///     vec![Blue.paint("brown fox")],
+/// ]);
+///
+/// let lines = wrap(&AnsiStrings(&strings), 4);
+/// assert_eq!(lines.len(), 6);
+/// assert_eq!(lines[3], [Blue.paint("brow")]);
+/// assert_eq!(
+///     AnsiStrings(&lines[3]).to_string(),
+///     "\x1b[34mbrow\x1b[0m",
+/// );
+/// ```
+pub fn wrap(strs: &AnsiStrings, width: usize) -> Vec<Vec<AnsiString<'static>>> {
+    let mut lines = Lines {
+        width,
+        done: Vec::new(),
+        line: Vec::new(),
+        line_width: 0,
+    };
+    let mut spaces = Vec::new();
+    let mut word = Vec::new();
+    let mut any_text = false;
+
+    for (index, i) in strs.0.iter().enumerate() {
+        if matches!(i.oscontrol, Some(OSControl::Title)) {
+            continue;
+        }
+        for (start, grapheme) in i.string.grapheme_indices(true) {
+            any_text = true;
+            let piece = Piece {
+                index,
+                range: start..start + grapheme.len(),
+                width: grapheme_width(grapheme),
+            };
+
+            if grapheme == "\n" || grapheme == "\r\n" {
+                if !word.is_empty() {
+                    lines.push_word(std::mem::take(&mut spaces), std::mem::take(&mut word));
+                }
+                spaces.clear();
+                lines.break_line();
+            } else if is_break(grapheme) {
+                if !word.is_empty() {
+                    lines.push_word(std::mem::take(&mut spaces), std::mem::take(&mut word));
+                }
+                spaces.push(piece);
+            } else {
+                word.push(piece);
+            }
+        }
+    }
+
+    if !any_text {
+        return Vec::new();
+    }
+    if !word.is_empty() {
+        lines.push_word(spaces, word);
+    }
+    lines.break_line();
+
+    lines
+        .done
+        .iter()
+        .map(|pieces| line_strings(strs, pieces))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::display::AnsiGenericString;
+    use crate::style::Color::*;
+
+    fn strings() -> [AnsiString<'static>; 3] {
+        [Red.paint("日本"), Blue.paint("-abc-"), Green.paint("語")]
+    }
+
+    #[test]
+    fn fits() {
+        let l = strings();
+        let a = AnsiStrings(&l);
+        let ellipsis = AnsiString::from("…");
+        assert_eq!(truncate(&a, 11, &ellipsis, Truncation::End), l);
+        assert_eq!(truncate(&a, 20, &ellipsis, Truncation::Middle), l);
+
+        let l = [
+            AnsiGenericString::title("Title"),
+            Red.paint("ab"),
+            Blue.paint("\u{200b}"),
+            Green.paint("\n"),
+        ];
+        assert_eq!(
+            truncate(&AnsiStrings(&l), 2, &ellipsis, Truncation::End),
+            &l[1..]
+        );
+    }
+
+    #[test]
+    fn end() {
+        let l = strings();
+        let a = AnsiStrings(&l);
+        let ellipsis = AnsiString::from("…");
+        assert_eq!(
+            truncate(&a, 8, &ellipsis, Truncation::End),
+            [Red.paint("日本"), Blue.paint("-ab"), Blue.paint("…")]
+        );
+        // the wide character doesn't fit in the one column left over
+        assert_eq!(
+            truncate(&a, 2, &ellipsis, Truncation::End),
+            [Red.paint("…")]
+        );
+    }
+
+    #[test]
+    fn start() {
+        let l = strings();
+        let a = AnsiStrings(&l);
+        let ellipsis = Yellow.paint("...");
+        assert_eq!(
+            truncate(&a, 8, &ellipsis, Truncation::Start),
+            [Yellow.paint("..."), Blue.paint("bc-"), Green.paint("語")]
+        );
+    }
+
+    #[test]
+    fn middle() {
+        let l = strings();
+        let a = AnsiStrings(&l);
+        let ellipsis = AnsiString::from("~");
+        assert_eq!(
+            truncate(&a, 8, &ellipsis, Truncation::Middle),
+            [
+                Red.paint("日本"),
+                Blue.paint("~"),
+                Blue.paint("-"),
+                Green.paint("語")
+            ]
+        );
+    }
+
+    #[test]
+    fn ellipsis_too_wide() {
+        let l = strings();
+        let a = AnsiStrings(&l);
+        let ellipsis = Yellow.paint("...");
+        assert_eq!(
+            truncate(&a, 2, &ellipsis, Truncation::End),
+            [Yellow.paint("..")]
+        );
+        assert_eq!(truncate(&a, 0, &ellipsis, Truncation::End), []);
+    }
+
+    #[test]
+    fn wrap_words() {
+        let l = [
+            Red.paint("one two "),
+            Blue.bold().paint("three"),
+            Green.paint(" four five"),
+        ];
+        let a = AnsiStrings(&l);
+        assert_eq!(
+            wrap(&a, 9),
+            [
+                vec![Red.paint("one two")],
+                vec![Blue.bold().paint("three")],
+                vec![Green.paint("four five")],
+            ]
+            .map(|line| line.to_vec())
+        );
+        assert_eq!(wrap(&a, 100), [l.to_vec()]);
+    }
+
+    #[test]
+    fn wrap_long_words() {
+        let l = [Red.paint("a 日本語です b")];
+        let a = AnsiStrings(&l);
+        assert_eq!(
+            wrap(&a, 5),
+            [
+                vec![Red.paint("a")],
+                vec![Red.paint("日本")],
+                vec![Red.paint("語で")],
+                vec![Red.paint("す b")],
+            ]
+        );
+        // a character wider than the line still gets a line of its own
+        assert_eq!(wrap(&a, 1)[1], [Red.paint("日")]);
+    }
+
+    #[test]
+    fn wrap_newlines() {
+        let l = [Red.paint("  indented\n\nnext \n"), Blue.paint("last")];
+        let a = AnsiStrings(&l);
+        assert_eq!(
+            wrap(&a, 20),
+            [
+                vec![Red.paint("  indented")],
+                vec![],
+                vec![Red.paint("next")],
+                vec![Blue.paint("last")],
+            ]
+        );
+        assert_eq!(
+            wrap(&AnsiStrings(&[Red.paint("x\n")]), 5),
+            [vec![Red.paint("x")], vec![]]
+        );
+        assert_eq!(wrap(&AnsiStrings(&[]), 5), Vec::<Vec<AnsiString>>::new());
+    }
+
+    #[test]
+    fn wrap_reopens_hyperlinks() {
+        let l = [Blue
+            .underline()
+            .paint("click here")
+            .hyperlink("https://example.com")];
+        let lines = wrap(&AnsiStrings(&l), 5);
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            let text = AnsiStrings(line).to_string();
+            assert!(text.contains("\x1B]8;;https://example.com\x1B\\"));
+            assert!(text.ends_with("\x1B[0m"));
+        }
+    }
+
+    #[test]
+    fn pad_alignment() {
+        let l = strings();
+        let a = AnsiStrings(&l);
+        let space = AnsiString::from(" ");
+        assert_eq!(
+            pad(&a, 13, Alignment::Left, &space),
+            [
+                l[0].clone(),
+                l[1].clone(),
+                l[2].clone(),
+                AnsiString::from("  ")
+            ]
+        );
+        assert_eq!(
+            pad(&a, 13, Alignment::Right, &space),
+            [
+                AnsiString::from("  "),
+                l[0].clone(),
+                l[1].clone(),
+                l[2].clone()
+            ]
+        );
+        assert_eq!(
+            pad(&a, 14, Alignment::Center, &space),
+            [
+                AnsiString::from(" "),
+                l[0].clone(),
+                l[1].clone(),
+                l[2].clone(),
+                AnsiString::from("  ")
+            ]
+        );
+        assert_eq!(pad(&a, 5, Alignment::Center, &space), l);
+
+        let l = [Red.paint("x\u{200b}"), Blue.paint("\u{200b}")];
+        assert_eq!(
+            pad(&AnsiStrings(&l), 3, Alignment::Left, &space),
+            [l[0].clone(), l[1].clone(), AnsiString::from("  ")]
+        );
+    }
+
+    #[test]
+    fn pad_fill() {
+        let l = [Red.paint("x")];
+        let a = AnsiStrings(&l);
+        assert_eq!(
+            pad(&a, 6, Alignment::Left, &Blue.paint("-=")),
+            [Red.paint("x"), Blue.paint("-=-=-")]
+        );
+        // a wide fill that doesn't divide the padding evenly
+        assert_eq!(
+            pad(&a, 4, Alignment::Right, &Blue.paint("語")),
+            [Blue.paint("語 "), Red.paint("x")]
+        );
+        assert_eq!(
+            pad(&a, 3, Alignment::Right, &AnsiString::from("")),
+            [AnsiString::from("  "), Red.paint("x")]
+        );
+    }
+
+    #[test]
+    fn display_ends_with_reset() {
+        let l = strings();
+        let a = AnsiStrings(&l);
+        let cut = truncate(&a, 5, &AnsiString::from("…"), Truncation::End);
+        assert!(AnsiStrings(&cut).to_string().ends_with("…\x1B[0m"));
+    }
+}
@@ -240,10 +240,15 @@
 doc_comment::doctest!("../README.md");
 
 pub mod ansi;
-pub use ansi::{Infix, Prefix, Suffix};
+pub use ansi::{Infix, Prefix, SgrParams, Suffix};
 
 mod style;
-pub use style::{Color, Style};
+pub use style::{Color, Style, UnderlineStyle};
+
+mod attributes;
+pub use attributes::Attributes;
+
+mod compose;
 
 mod difference;
 mod display;
@@ -258,6 +263,14 @@ pub use crate::windows::*;
 mod util;
 pub use util::*;
 
+mod parse;
+pub use parse::parse_ansi;
+
+#[cfg(feature = "unicode_width")]
+mod layout;
+#[cfg(feature = "unicode_width")]
+pub use layout::*;
+
 mod debug;
 
 pub mod gradient;
@@ -265,3 +278,34 @@ pub use gradient::*;
 
 mod rgb;
 pub use rgb::*;
+
+mod downsample;
+
+mod render;
+pub use render::{ColorDepth, Rendered};
+
+mod color_spec;
+pub use color_spec::ParseColorError;
+
+#[cfg(feature = "named_colors")]
+mod named_colors;
+#[cfg(feature = "named_colors")]
+pub use named_colors::{named_color, named_colors};
+
+mod ls_colors;
+pub use ls_colors::{Indicator, LsColors};
+
+mod spec;
+pub use spec::ParseStyleError;
+
+#[cfg(feature = "derive_serde_style")]
+pub mod serde_human;
+
+mod theme;
+pub use theme::Theme;
+
+mod palette;
+pub use palette::{Palette, ParsePaletteError};
+
+mod terminal;
+pub use terminal::{colors_enabled, no_color, set_colors_enabled, Capabilities, Transitions};
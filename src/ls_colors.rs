@@ -0,0 +1,461 @@
+use crate::parse::apply_sgr;
+use crate::style::Style;
+use std::fs::{self, Metadata};
+use std::path::Path;
+
+/// The kinds of file that `LS_COLORS` can give a style to, named after the
+/// two-letter keys used by GNU `ls` and `dircolors`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Indicator {
+    /// Text that is not a file name (`no`).
+    Normal,
+    /// A regular file (`fi`).
+    File,
+    /// A directory (`di`).
+    Directory,
+    /// A symbolic link (`ln`).
+    Symlink,
+    /// A regular file with more than one hard link (`mh`).
+    MultiHardLink,
+    /// A named pipe (`pi`).
+    Pipe,
+    /// A socket (`so`).
+    Socket,
+    /// A door (`do`).
+    Door,
+    /// A block device (`bd`).
+    BlockDevice,
+    /// A character device (`cd`).
+    CharDevice,
+    /// A symbolic link to a file that doesn’t exist (`or`).
+    Orphan,
+    /// A file that doesn’t exist, such as the target of an orphan (`mi`).
+    Missing,
+    /// A file that is setuid (`su`).
+    Setuid,
+    /// A file that is setgid (`sg`).
+    Setgid,
+    /// A file with capabilities (`ca`).
+    Capability,
+    /// A directory that is both sticky and writable by others (`tw`).
+    StickyOtherWritable,
+    /// A directory that is writable by others (`ow`).
+    OtherWritable,
+    /// A directory with the sticky bit set (`st`).
+    Sticky,
+    /// An executable file (`ex`).
+    Executable,
+}
+
+impl Indicator {
+    const ALL: [Indicator; 19] = [
+        Indicator::Normal,
+        Indicator::File,
+        Indicator::Directory,
+        Indicator::Symlink,
+        Indicator::MultiHardLink,
+        Indicator::Pipe,
+        Indicator::Socket,
+        Indicator::Door,
+        Indicator::BlockDevice,
+        Indicator::CharDevice,
+        Indicator::Orphan,
+        Indicator::Missing,
+        Indicator::Setuid,
+        Indicator::Setgid,
+        Indicator::Capability,
+        Indicator::StickyOtherWritable,
+        Indicator::OtherWritable,
+        Indicator::Sticky,
+        Indicator::Executable,
+    ];
+
+    /// Returns the two-letter `LS_COLORS` key for this kind of file.
+    pub fn code(self) -> &'static str {
+        match self {
+            Indicator::Normal => "no",
+            Indicator::File => "fi",
+            Indicator::Directory => "di",
+            Indicator::Symlink => "ln",
+            Indicator::MultiHardLink => "mh",
+            Indicator::Pipe => "pi",
+            Indicator::Socket => "so",
+            Indicator::Door => "do",
+            Indicator::BlockDevice => "bd",
+            Indicator::CharDevice => "cd",
+            Indicator::Orphan => "or",
+            Indicator::Missing => "mi",
+            Indicator::Setuid => "su",
+            Indicator::Setgid => "sg",
+            Indicator::Capability => "ca",
+            Indicator::StickyOtherWritable => "tw",
+            Indicator::OtherWritable => "ow",
+            Indicator::Sticky => "st",
+            Indicator::Executable => "ex",
+        }
+    }
+
+    /// Returns the kind of file with the given two-letter `LS_COLORS` key.
+    pub fn from_code(code: &str) -> Option<Indicator> {
+        Indicator::ALL
+            .into_iter()
+            .find(|indicator| indicator.code() == code)
+    }
+}
+
+/// The file styles given by an `LS_COLORS` string, as written by GNU
+/// `dircolors`.
+///
+/// The string is a colon-separated list of `key=value` entries, where the
+/// key is either the two-letter code of an [`Indicator`], such as `di` for
+/// directories, or a pattern like `*.tar` that matches the end of a file
+/// name, and the value is the list of SGR parameters to use, such as
+/// `01;34`. When a key appears more than once, the later entry wins, as it
+/// does in `ls`. Patterns match regardless of case, except that, as in GNU
+/// `ls`, patterns that differ only by case and give different styles, such
+/// as `*.gz` and `*.GZ`, each match only their own case.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{Color, Indicator, LsColors};
+///
+/// let ls_colors = LsColors::parse("di=01;34:ln=01;36:*.tar=01;31");
+/// assert_eq!(ls_colors.style_for_indicator(Indicator::Directory), Some(Color::Blue.bold()));
+/// assert_eq!(ls_colors.style_for_name("backup.tar"), Some(Color::Red.bold()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsColors {
+    indicators: [Option<Style>; 19],
+    suffixes: Vec<Suffix>,
+    symlinks_as_target: bool,
+}
+
+/// A pattern like `*.tar`, without its leading `*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Suffix {
+    text: String,
+    style: Style,
+    exact_case: bool,
+}
+
+impl Suffix {
+    fn matches(&self, name: &str, lowercase_name: &str) -> bool {
+        if self.exact_case {
+            name.ends_with(&self.text)
+        } else {
+            lowercase_name.ends_with(&self.text.to_lowercase())
+        }
+    }
+}
+
+impl LsColors {
+    /// Parses an `LS_COLORS` string. Entries that can’t be understood, such
+    /// as ones without an `=` or with an unknown key, are skipped.
+    pub fn parse(input: &str) -> LsColors {
+        let mut ls_colors = LsColors::default();
+        for entry in input.split(':') {
+            let (key, value) = match entry.split_once('=') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            if key == "ln" && value == "target" {
+                ls_colors.symlinks_as_target = true;
+                continue;
+            }
+
+            let style = apply_sgr(Style::default(), value.as_bytes());
+            if let Some(suffix) = key.strip_prefix('*') {
+                ls_colors.suffixes.push(Suffix {
+                    text: suffix.to_owned(),
+                    style,
+                    exact_case: false,
+                });
+            } else if let Some(indicator) = Indicator::from_code(key) {
+                ls_colors.indicators[indicator as usize] = Some(style);
+                if indicator == Indicator::Symlink {
+                    ls_colors.symlinks_as_target = false;
+                }
+            }
+        }
+
+        for i in 0..ls_colors.suffixes.len() {
+            let suffix = &ls_colors.suffixes[i];
+            let exact_case = ls_colors.suffixes.iter().any(|other| {
+                other.text != suffix.text
+                    && other.text.to_lowercase() == suffix.text.to_lowercase()
+                    && other.style != suffix.style
+            });
+            ls_colors.suffixes[i].exact_case = exact_case;
+        }
+        ls_colors
+    }
+
+    /// Parses the `LS_COLORS` environment variable, if it is set.
+    pub fn from_env() -> Option<LsColors> {
+        std::env::var("LS_COLORS")
+            .ok()
+            .map(|value| LsColors::parse(&value))
+    }
+
+    /// Returns the style for the given kind of file, if there is one.
+    ///
+    /// An entry that turns the colors off, such as `ex=00`, counts as
+    /// having no style, so that the file is styled as a less specific kind.
+    pub fn style_for_indicator(&self, indicator: Indicator) -> Option<Style> {
+        self.indicators[indicator as usize].filter(|style| !style.is_plain())
+    }
+
+    /// Returns the style of the last pattern that matches the end of the
+    /// given file name, if there is one.
+    ///
+    /// Case is ignored, unless another pattern differs from this one only
+    /// by case and gives a different style, which is the rule GNU `ls`
+    /// follows.
+    pub fn style_for_name(&self, name: &str) -> Option<Style> {
+        let lowercase_name = name.to_lowercase();
+        self.suffixes
+            .iter()
+            .rev()
+            .find(|suffix| suffix.matches(name, &lowercase_name))
+            .map(|suffix| suffix.style)
+    }
+
+    /// Returns the style for the file at the given path, reading its
+    /// metadata without following symbolic links.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use nu_ansi_term::LsColors;
+    ///
+    /// let ls_colors = LsColors::from_env().unwrap_or_default();
+    /// for entry in std::fs::read_dir(".").unwrap() {
+    ///     let path = entry.unwrap().path();
+    ///     let style = ls_colors.style_for_path(&path).unwrap_or_default();
+    ///     println!("{}", style.paint(path.display().to_string()));
+    /// }
+    /// ```
+    pub fn style_for_path(&self, path: &Path) -> Option<Style> {
+        let metadata = fs::symlink_metadata(path).ok();
+        self.style_for_path_with_metadata(path, metadata.as_ref())
+    }
+
+    /// Returns the style for the file at the given path, using metadata
+    /// that has already been read with [`fs::symlink_metadata`], or `None`
+    /// if the file doesn’t exist.
+    ///
+    /// Regular files are matched against the patterns only when they are
+    /// not setuid, setgid, executable or multiply linked, with a style for
+    /// that kind of file.
+    pub fn style_for_path_with_metadata(
+        &self,
+        path: &Path,
+        metadata: Option<&Metadata>,
+    ) -> Option<Style> {
+        let metadata = match metadata {
+            Some(metadata) => metadata,
+            None => return self.style_for_indicator(Indicator::Missing),
+        };
+
+        if metadata.file_type().is_symlink() {
+            return match fs::metadata(path) {
+                Err(_) => self
+                    .style_for_indicator(Indicator::Orphan)
+                    .or_else(|| self.style_for_indicator(Indicator::Symlink)),
+                Ok(target) if self.symlinks_as_target => {
+                    let target_path = fs::read_link(path).unwrap_or_else(|_| path.to_owned());
+                    self.style_for_path_with_metadata(&target_path, Some(&target))
+                }
+                Ok(_) => self.style_for_indicator(Indicator::Symlink),
+            };
+        }
+
+        let indicator = self.indicator_for(metadata);
+        if indicator == Indicator::File {
+            let name = path.file_name().unwrap_or(path.as_os_str());
+            if let Some(style) = self.style_for_name(&name.to_string_lossy()) {
+                return Some(style);
+            }
+        }
+        self.style_for_indicator(indicator)
+            .or_else(|| self.style_for_indicator(Indicator::File))
+            .or_else(|| self.style_for_indicator(Indicator::Normal))
+    }
+
+    /// Picks the most specific kind of file that has a style.
+    fn indicator_for(&self, metadata: &Metadata) -> Indicator {
+        let file_type = metadata.file_type();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+            let has_style = |indicator| self.style_for_indicator(indicator).is_some();
+            let mode = metadata.mode();
+            if file_type.is_file() {
+                return [
+                    (mode & 0o4000 != 0, Indicator::Setuid),
+                    (mode & 0o2000 != 0, Indicator::Setgid),
+                    (mode & 0o111 != 0, Indicator::Executable),
+                    (metadata.nlink() > 1, Indicator::MultiHardLink),
+                ]
+                .into_iter()
+                .find(|&(applies, indicator)| applies && has_style(indicator))
+                .map_or(Indicator::File, |(_, indicator)| indicator);
+            } else if file_type.is_dir() {
+                let sticky = mode & 0o1000 != 0;
+                let other_writable = mode & 0o002 != 0;
+                return [
+                    (sticky && other_writable, Indicator::StickyOtherWritable),
+                    (other_writable, Indicator::OtherWritable),
+                    (sticky, Indicator::Sticky),
+                ]
+                .into_iter()
+                .find(|&(applies, indicator)| applies && has_style(indicator))
+                .map_or(Indicator::Directory, |(_, indicator)| indicator);
+            } else if file_type.is_fifo() {
+                return Indicator::Pipe;
+            } else if file_type.is_socket() {
+                return Indicator::Socket;
+            } else if file_type.is_block_device() {
+                return Indicator::BlockDevice;
+            } else if file_type.is_char_device() {
+                return Indicator::CharDevice;
+            }
+        }
+
+        if file_type.is_dir() {
+            Indicator::Directory
+        } else {
+            Indicator::File
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Indicator, LsColors};
+    use crate::style::Color::*;
+    use crate::style::{Style, UnderlineStyle};
+
+    #[test]
+    fn indicators() {
+        let ls_colors = LsColors::parse("rs=0:di=01;34:ln=01;36:mh=00:pi=40;33:ex=01;32:bogus");
+        assert_eq!(
+            ls_colors.style_for_indicator(Indicator::Directory),
+            Some(Blue.bold())
+        );
+        assert_eq!(
+            ls_colors.style_for_indicator(Indicator::Pipe),
+            Some(Yellow.on(Black))
+        );
+        assert_eq!(
+            ls_colors.style_for_indicator(Indicator::MultiHardLink),
+            None
+        );
+        assert_eq!(ls_colors.style_for_indicator(Indicator::Socket), None);
+        assert_eq!(
+            Indicator::from_code("tw"),
+            Some(Indicator::StickyOtherWritable)
+        );
+        assert_eq!(Indicator::from_code("zz"), None);
+    }
+
+    #[test]
+    fn later_entries_win() {
+        let ls_colors = LsColors::parse("di=34:*.gz=31:*.tar.gz=35:*.GZ=33:di=36");
+        assert_eq!(
+            ls_colors.style_for_indicator(Indicator::Directory),
+            Some(Cyan.normal())
+        );
+        assert_eq!(ls_colors.style_for_name("a.tar.gz"), Some(Purple.normal()));
+        assert_eq!(ls_colors.style_for_name("a.TGZ"), None);
+        assert_eq!(ls_colors.style_for_name("README"), None);
+        assert_eq!(LsColors::parse(""), LsColors::default());
+    }
+
+    #[test]
+    fn case() {
+        let ls_colors = LsColors::parse("*.txt=32:*.gz=31:*.GZ=33:*.md=34:*.MD=34");
+        assert_eq!(ls_colors.style_for_name("a.TXT"), Some(Green.normal()));
+        assert_eq!(ls_colors.style_for_name("a.gz"), Some(Red.normal()));
+        assert_eq!(ls_colors.style_for_name("a.GZ"), Some(Yellow.normal()));
+        assert_eq!(ls_colors.style_for_name("a.Gz"), None);
+        assert_eq!(ls_colors.style_for_name("a.Md"), Some(Blue.normal()));
+    }
+
+    #[test]
+    fn extended_colors() {
+        let ls_colors = LsColors::parse("*.rs=38;5;208:*.md=4;38;2;10;20;30");
+        assert_eq!(
+            ls_colors.style_for_name("lib.rs"),
+            Some(Fixed(208).normal())
+        );
+        assert_eq!(
+            ls_colors.style_for_name("README.md"),
+            Some(Style::new().fg(Rgb(10, 20, 30)).underline())
+        );
+    }
+
+    #[test]
+    fn sgr_params_round_trip() {
+        let curly = Red
+            .on(Rgb(1, 2, 3))
+            .bold()
+            .underline_style(UnderlineStyle::Curly)
+            .underline_color(Rgb(4, 5, 6));
+        let ls_colors = LsColors::parse(&format!(
+            "di={}:*.txt={}:ex=01;32",
+            curly.sgr_params(),
+            Blue.underline().underline_color(Fixed(200)).sgr_params()
+        ));
+        assert_eq!(
+            ls_colors.style_for_indicator(Indicator::Directory),
+            Some(curly.underline_style(UnderlineStyle::Single))
+        );
+        assert_eq!(
+            ls_colors.style_for_name("notes.txt"),
+            Some(Blue.underline().underline_color(Fixed(200)))
+        );
+        assert_eq!(
+            ls_colors.style_for_indicator(Indicator::Executable),
+            Some(Green.bold())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn paths() {
+        use std::fs;
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir =
+            std::env::temp_dir().join(format!("nu-ansi-term-ls-colors-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).unwrap();
+        let file = dir.join("notes.txt");
+        fs::write(&file, "").unwrap();
+        let script = dir.join("run.txt");
+        fs::write(&script, "").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        symlink(&file, dir.join("link")).unwrap();
+        symlink(dir.join("nowhere"), dir.join("orphan")).unwrap();
+
+        let ls_colors = LsColors::parse("fi=37:di=34:ln=36:or=31:ex=32:*.txt=33");
+        let style = |name: &str| ls_colors.style_for_path(&dir.join(name));
+        assert_eq!(style(""), Some(Blue.normal()));
+        assert_eq!(style("notes.txt"), Some(Yellow.normal()));
+        assert_eq!(style("run.txt"), Some(Green.normal()));
+        assert_eq!(style("link"), Some(Cyan.normal()));
+        assert_eq!(style("orphan"), Some(Red.normal()));
+        assert_eq!(style("missing"), None);
+
+        let ls_colors = LsColors::parse("ln=36:ln=target:mi=35:*.txt=33");
+        let style = |name: &str| ls_colors.style_for_path(&dir.join(name));
+        assert_eq!(style("link"), Some(Yellow.normal()));
+        assert_eq!(style("missing"), Some(Purple.normal()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,742 @@
+use crate::downsample::{fixed_to_rgb, XTERM_16};
+use crate::rgb::Rgb;
+use crate::style::Color;
+use std::error::Error;
+use std::fmt;
+
+/// The colors that a terminal shows for the sixteen basic colors, and for
+/// text and its background when no color is set.
+///
+/// Terminals let their users pick these colors, usually through a color
+/// scheme, so they can be loaded from the files of a few common scheme
+/// formats. Colors that a scheme leaves out keep the xterm defaults, except
+/// in base16 schemes, which must be complete.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{Color, Palette, Rgb};
+///
+/// let palette = Palette::from_kitty_conf("color1 #cc241d\nforeground #ebdbb2").unwrap();
+/// assert_eq!(Color::Red.to_rgb(&palette), Rgb::new(0xcc, 0x24, 0x1d));
+/// assert_eq!(Color::Default.to_rgb(&palette), Rgb::new(0xeb, 0xdb, 0xb2));
+/// assert_eq!(Color::Fixed(1).to_rgb(&palette), Rgb::new(0xcc, 0x24, 0x1d));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    /// Black, red, green, yellow, blue, purple, cyan and white, followed by
+    /// their bright versions, as palette entries 0 to 15.
+    pub colors: [Rgb; 16],
+
+    /// The color of text that has no foreground color.
+    pub foreground: Rgb,
+
+    /// The color behind text that has no background color.
+    pub background: Rgb,
+}
+
+impl Palette {
+    /// The colors xterm uses by default, with light gray text on black.
+    pub const XTERM: Palette = Palette {
+        colors: XTERM_16,
+        foreground: XTERM_16[7],
+        background: XTERM_16[0],
+    };
+
+    /// Returns the color of an entry in the 256-color palette. The first
+    /// sixteen come from this palette, and the others from the standard
+    /// color cube and grayscale ramp.
+    pub fn color(&self, index: u8) -> Rgb {
+        match self.colors.get(index as usize) {
+            Some(rgb) => *rgb,
+            None => fixed_to_rgb(index),
+        }
+    }
+
+    /// Loads a base16 scheme, written in YAML with keys from `base00` to
+    /// `base0F`, using the same mapping onto terminal colors as
+    /// `base16-shell`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Palette, Rgb};
+    ///
+    /// let scheme = "
+    /// scheme: \"Example\"
+    /// base00: \"181818\"
+    /// base01: \"282828\"
+    /// base02: \"383838\"
+    /// base03: \"585858\"
+    /// base04: \"b8b8b8\"
+    /// base05: \"d8d8d8\"
+    /// base06: \"e8e8e8\"
+    /// base07: \"f8f8f8\"
+    /// base08: \"ab4642\"
+    /// base09: \"dc9656\"
+    /// base0A: \"f7ca88\"
+    /// base0B: \"a1b56c\"
+    /// base0C: \"86c1b9\"
+    /// base0D: \"7cafc2\"
+    /// base0E: \"ba8baf\"
+    /// base0F: \"a16946\"
+    /// ";
+    /// let palette = Palette::from_base16_yaml(scheme).unwrap();
+    /// assert_eq!(palette.colors[1], Rgb::new(0xab, 0x46, 0x42));
+    /// assert_eq!(palette.background, Rgb::new(0x18, 0x18, 0x18));
+    /// ```
+    pub fn from_base16_yaml(input: &str) -> Result<Palette, ParsePaletteError> {
+        let mut bases = [None; 16];
+        for line in input.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let key = key.trim();
+            let index = match key
+                .strip_prefix("base0")
+                .and_then(|digit| u8::from_str_radix(digit, 16).ok())
+            {
+                Some(index) if key.len() == 6 => index,
+                _ => continue,
+            };
+            bases[index as usize] = Some(parse_rgb(key, scalar(value))?);
+        }
+
+        let base = |index: usize| {
+            bases[index].ok_or_else(|| {
+                ParsePaletteError::new(ErrorKind::Missing, &format!("base0{:X}", index), "")
+            })
+        };
+        // Black, red, green, yellow, blue, magenta, cyan and white.
+        let normal = [0x0, 0x8, 0xB, 0xA, 0xD, 0xE, 0xC, 0x5];
+        let bright = [0x3, 0x8, 0xB, 0xA, 0xD, 0xE, 0xC, 0x7];
+
+        let mut colors = [Rgb::new(0, 0, 0); 16];
+        for (slot, index) in normal.into_iter().chain(bright).enumerate() {
+            colors[slot] = base(index)?;
+        }
+        Ok(Palette {
+            colors,
+            foreground: base(0x5)?,
+            background: base(0x0)?,
+        })
+    }
+
+    /// Loads the colors from an Alacritty configuration file in TOML, from
+    /// the `[colors.primary]`, `[colors.normal]` and `[colors.bright]`
+    /// tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Palette, Rgb};
+    ///
+    /// let config = "
+    /// [colors.primary]
+    /// background = '#1d1f21'
+    /// foreground = '#c5c8c6'
+    ///
+    /// [colors.normal]
+    /// red = '#cc6666'
+    /// ";
+    /// let palette = Palette::from_alacritty_toml(config).unwrap();
+    /// assert_eq!(palette.colors[1], Rgb::new(0xcc, 0x66, 0x66));
+    /// assert_eq!(palette.foreground, Rgb::new(0xc5, 0xc8, 0xc6));
+    /// ```
+    pub fn from_alacritty_toml(input: &str) -> Result<Palette, ParsePaletteError> {
+        let mut loader = Loader::new();
+        let mut table = String::new();
+        for line in input.lines() {
+            let line = line.trim();
+            if let Some(header) = line.strip_prefix('[') {
+                table = header
+                    .split(']')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_owned();
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let path = if table.is_empty() {
+                key.trim().to_owned()
+            } else {
+                format!("{}.{}", table, key.trim())
+            };
+            let slot = match path.strip_prefix("colors.") {
+                Some("primary.foreground") => Slot::Foreground,
+                Some("primary.background") => Slot::Background,
+                Some(rest) => match rest.split_once('.') {
+                    Some(("normal", name)) => match ansi_name(name) {
+                        Some(index) => Slot::Color(index),
+                        None => continue,
+                    },
+                    Some(("bright", name)) => match ansi_name(name) {
+                        Some(index) => Slot::Color(index + 8),
+                        None => continue,
+                    },
+                    _ => continue,
+                },
+                None => continue,
+            };
+            loader.set(slot, parse_rgb(&path, scalar(value))?);
+        }
+        loader.finish()
+    }
+
+    /// Loads a Windows Terminal color scheme: a JSON object with keys such
+    /// as `red`, `brightRed`, `foreground` and `background`, as found in the
+    /// `schemes` list of its settings.
+    ///
+    /// Only the members of that one object are read, so a whole
+    /// `settings.json` file, where the schemes are nested inside it, has
+    /// no colors of its own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Palette, Rgb};
+    ///
+    /// let scheme = r##"{
+    ///     "name": "Campbell",
+    ///     "background": "#0C0C0C",
+    ///     "brightBlue": "#3B78FF",
+    ///     "purple": "#881798"
+    /// }"##;
+    /// let palette = Palette::from_windows_terminal_json(scheme).unwrap();
+    /// assert_eq!(palette.colors[12], Rgb::new(0x3b, 0x78, 0xff));
+    /// assert_eq!(palette.colors[5], Rgb::new(0x88, 0x17, 0x98));
+    /// ```
+    pub fn from_windows_terminal_json(input: &str) -> Result<Palette, ParsePaletteError> {
+        let mut loader = Loader::new();
+        for (key, value) in json_string_members(input) {
+            let lower = key.to_ascii_lowercase();
+            let slot = match lower.as_str() {
+                "foreground" => Slot::Foreground,
+                "background" => Slot::Background,
+                name => match name.strip_prefix("bright") {
+                    Some(name) => match ansi_name(name) {
+                        Some(index) => Slot::Color(index + 8),
+                        None => continue,
+                    },
+                    None => match ansi_name(name) {
+                        Some(index) => Slot::Color(index),
+                        None => continue,
+                    },
+                },
+            };
+            loader.set(slot, parse_rgb(&key, &value)?);
+        }
+        loader.finish()
+    }
+
+    /// Loads an iTerm2 color scheme, the XML property list saved in
+    /// `.itermcolors` files, with entries from `Ansi 0 Color` to
+    /// `Ansi 15 Color`, `Foreground Color` and `Background Color`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Palette, Rgb};
+    ///
+    /// let scheme = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <plist version="1.0">
+    /// <dict>
+    ///     <key>Ansi 2 Color</key>
+    ///     <dict>
+    ///         <key>Blue Component</key>
+    ///         <real>0.0</real>
+    ///         <key>Green Component</key>
+    ///         <real>0.8</real>
+    ///         <key>Red Component</key>
+    ///         <real>0.4</real>
+    ///     </dict>
+    /// </dict>
+    /// </plist>"#;
+    /// let palette = Palette::from_iterm2_plist(scheme).unwrap();
+    /// assert_eq!(palette.colors[2], Rgb::new(102, 204, 0));
+    /// ```
+    pub fn from_iterm2_plist(input: &str) -> Result<Palette, ParsePaletteError> {
+        let mut loader = Loader::new();
+        let mut depth = 0;
+        let mut key = String::new();
+        let mut entry: Option<(String, [f32; 3])> = None;
+
+        let mut rest = input;
+        while let Some(start) = rest.find('<') {
+            let end = match rest[start..].find('>') {
+                Some(end) => start + end,
+                None => break,
+            };
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+            let text = rest[..rest.find('<').unwrap_or(rest.len())].trim();
+
+            match tag.split_whitespace().next().unwrap_or_default() {
+                "dict" => {
+                    depth += 1;
+                    if depth == 2 {
+                        entry = Some((std::mem::take(&mut key), [0.0; 3]));
+                    }
+                }
+                "/dict" => {
+                    if depth == 2 {
+                        if let Some((name, [r, g, b])) = entry.take() {
+                            if let Some(slot) = iterm2_slot(&name) {
+                                loader.set(slot, Rgb::new(channel(r), channel(g), channel(b)));
+                            }
+                        }
+                    }
+                    depth -= 1;
+                }
+                "key" => key = text.to_owned(),
+                "real" | "integer" if depth == 2 => {
+                    let channel = match key.as_str() {
+                        "Red Component" => 0,
+                        "Green Component" => 1,
+                        "Blue Component" => 2,
+                        _ => continue,
+                    };
+                    let value = text
+                        .parse()
+                        .map_err(|_| ParsePaletteError::new(ErrorKind::InvalidColor, &key, text))?;
+                    if let Some((_, channels)) = &mut entry {
+                        channels[channel] = value;
+                    }
+                }
+                _ => {}
+            }
+        }
+        loader.finish()
+    }
+
+    /// Loads the colors from a kitty configuration file or theme, from the
+    /// `color0` to `color15`, `foreground` and `background` options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Palette, Rgb};
+    ///
+    /// let theme = "
+    /// # Gruvbox
+    /// background #282828
+    /// color9     #fb4934
+    /// ";
+    /// let palette = Palette::from_kitty_conf(theme).unwrap();
+    /// assert_eq!(palette.colors[9], Rgb::new(0xfb, 0x49, 0x34));
+    /// assert_eq!(palette.background, Rgb::new(0x28, 0x28, 0x28));
+    /// ```
+    pub fn from_kitty_conf(input: &str) -> Result<Palette, ParsePaletteError> {
+        let mut loader = Loader::new();
+        for line in input.lines() {
+            let mut words = line.split_whitespace();
+            let (key, value) = match (words.next(), words.next()) {
+                (Some(key), Some(value)) => (key, value),
+                _ => continue,
+            };
+            let slot = match key {
+                "foreground" => Slot::Foreground,
+                "background" => Slot::Background,
+                _ => match key.strip_prefix("color").and_then(|n| n.parse().ok()) {
+                    Some(index) if index < 16 => Slot::Color(index),
+                    _ => continue,
+                },
+            };
+            loader.set(slot, parse_rgb(key, value)?);
+        }
+        loader.finish()
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::XTERM
+    }
+}
+
+impl Color {
+    /// Returns the color that this color is shown as by a terminal that uses
+    /// the given palette.
+    ///
+    /// The basic colors and the first sixteen `Fixed` colors come from the
+    /// palette, and `Default` is its foreground color. The background for
+    /// text without a background color is `palette.background`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, Palette, Rgb};
+    ///
+    /// let palette = Palette::default();
+    /// assert_eq!(Color::LightRed.to_rgb(&palette), Rgb::new(255, 0, 0));
+    /// assert_eq!(Color::Fixed(208).to_rgb(&palette), Rgb::new(255, 135, 0));
+    /// assert_eq!(Color::Rgb(1, 2, 3).to_rgb(&palette), Rgb::new(1, 2, 3));
+    /// ```
+    pub fn to_rgb(self, palette: &Palette) -> Rgb {
+        let index = match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Purple | Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::DarkGray => 8,
+            Color::LightRed => 9,
+            Color::LightGreen => 10,
+            Color::LightYellow => 11,
+            Color::LightBlue => 12,
+            Color::LightPurple | Color::LightMagenta => 13,
+            Color::LightCyan => 14,
+            Color::LightGray => 15,
+            Color::Fixed(index) => index,
+            Color::Rgb(r, g, b) => return Rgb::new(r, g, b),
+            Color::Default => return palette.foreground,
+        };
+        palette.color(index)
+    }
+}
+
+/// An error from loading a `Palette` from a color scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePaletteError {
+    kind: ErrorKind,
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+    InvalidColor,
+    Missing,
+    Empty,
+}
+
+impl ParsePaletteError {
+    fn new(kind: ErrorKind, key: &str, value: &str) -> ParsePaletteError {
+        ParsePaletteError {
+            kind,
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    /// The key of the color that is invalid or missing, if there is one.
+    pub fn key(&self) -> Option<&str> {
+        match self.kind {
+            ErrorKind::Empty => None,
+            _ => Some(&self.key),
+        }
+    }
+}
+
+impl fmt::Display for ParsePaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::InvalidColor => {
+                write!(f, "invalid color `{}` for `{}`", self.value, self.key)
+            }
+            ErrorKind::Missing => write!(f, "missing color `{}`", self.key),
+            ErrorKind::Empty => f.write_str("no colors found in the color scheme"),
+        }
+    }
+}
+
+impl Error for ParsePaletteError {}
+
+/// Where a color from a scheme goes in the palette.
+#[derive(Clone, Copy)]
+enum Slot {
+    Color(usize),
+    Foreground,
+    Background,
+}
+
+/// A palette being loaded, starting from the xterm colors.
+struct Loader {
+    palette: Palette,
+    found_any: bool,
+}
+
+impl Loader {
+    fn new() -> Loader {
+        Loader {
+            palette: Palette::XTERM,
+            found_any: false,
+        }
+    }
+
+    fn set(&mut self, slot: Slot, rgb: Rgb) {
+        match slot {
+            Slot::Color(index) => self.palette.colors[index] = rgb,
+            Slot::Foreground => self.palette.foreground = rgb,
+            Slot::Background => self.palette.background = rgb,
+        }
+        self.found_any = true;
+    }
+
+    fn finish(self) -> Result<Palette, ParsePaletteError> {
+        if self.found_any {
+            Ok(self.palette)
+        } else {
+            Err(ParsePaletteError::new(ErrorKind::Empty, "", ""))
+        }
+    }
+}
+
+/// Returns the index of one of the eight basic colors from its name.
+fn ansi_name(name: &str) -> Option<usize> {
+    let names = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    match name {
+        "purple" => Some(5),
+        _ => names.iter().position(|n| *n == name),
+    }
+}
+
+fn iterm2_slot(name: &str) -> Option<Slot> {
+    match name {
+        "Foreground Color" => Some(Slot::Foreground),
+        "Background Color" => Some(Slot::Background),
+        _ => {
+            let index = name
+                .strip_prefix("Ansi ")?
+                .strip_suffix(" Color")?
+                .parse()
+                .ok()?;
+            (index < 16).then_some(Slot::Color(index))
+        }
+    }
+}
+
+/// Converts a color channel from 0.0 to 1.0 into a byte.
+fn channel(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Returns the value of a YAML or TOML scalar, without quotes around it or
+/// a comment after it.
+fn scalar(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(quote) {
+            return rest.split(quote).next().unwrap_or_default();
+        }
+    }
+    value.split(" #").next().unwrap_or_default().trim()
+}
+
+/// Parses a hex color from a scheme, where the `#` is optional.
+fn parse_rgb(key: &str, value: &str) -> Result<Rgb, ParsePaletteError> {
+    let parsed = if value.len() == 6 && value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        format!("#{}", value).parse()
+    } else {
+        value.parse()
+    };
+    parsed.map_err(|_| ParsePaletteError::new(ErrorKind::InvalidColor, key, value))
+}
+
+/// Returns the members of the JSON object that `input` starts with whose
+/// values are strings. Members with other values, including nested objects
+/// and arrays, are skipped, as is anything after the object.
+fn json_string_members(input: &str) -> Vec<(String, String)> {
+    let mut members = Vec::new();
+    let mut chars = input.trim_start().chars();
+    if chars.next() != Some('{') {
+        return members;
+    }
+
+    let mut depth = 1;
+    let mut key = None;
+    let mut in_value = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let string = json_string(&mut chars);
+                if depth == 1 && in_value {
+                    if let Some(key) = key.take() {
+                        members.push((key, string));
+                    }
+                } else if depth == 1 {
+                    key = Some(string);
+                }
+            }
+            '{' | '[' => {
+                if depth == 1 {
+                    key = None;
+                }
+                depth += 1;
+            }
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            ':' if depth == 1 => in_value = true,
+            ',' if depth == 1 => {
+                in_value = false;
+                key = None;
+            }
+            c if depth == 1 && !c.is_whitespace() => key = None,
+            _ => {}
+        }
+    }
+    members
+}
+
+/// Reads the rest of a JSON string whose opening quote has been read,
+/// unescaping only the characters that are escaped as themselves.
+fn json_string(chars: &mut std::str::Chars) -> String {
+    let mut string = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    string.push(escaped);
+                }
+            }
+            c => string.push(c),
+        }
+    }
+    string
+}
+
+#[cfg(test)]
+mod test {
+    use super::Palette;
+    use crate::rgb::Rgb;
+    use crate::style::Color;
+
+    #[test]
+    fn to_rgb() {
+        let mut palette = Palette::XTERM;
+        palette.colors[5] = Rgb::new(1, 2, 3);
+        palette.foreground = Rgb::new(4, 5, 6);
+        assert_eq!(Color::Purple.to_rgb(&palette), Rgb::new(1, 2, 3));
+        assert_eq!(Color::Magenta.to_rgb(&palette), Rgb::new(1, 2, 3));
+        assert_eq!(Color::Fixed(5).to_rgb(&palette), Rgb::new(1, 2, 3));
+        assert_eq!(Color::Default.to_rgb(&palette), Rgb::new(4, 5, 6));
+        assert_eq!(Color::Fixed(16).to_rgb(&palette), Rgb::new(0, 0, 0));
+        assert_eq!(Color::Fixed(255).to_rgb(&palette), Rgb::gray(238));
+    }
+
+    #[test]
+    fn base16() {
+        let scheme = (0..16)
+            .map(|i| format!("  base0{:X}: \"#{:02x}0000\" # comment\n", i, i))
+            .collect::<String>();
+        let palette = Palette::from_base16_yaml(&format!("palette:\n{}", scheme)).unwrap();
+        let red = |i: u8| Rgb::new(i, 0, 0);
+        assert_eq!(palette.colors[0], red(0x0));
+        assert_eq!(palette.colors[4], red(0xd));
+        assert_eq!(palette.colors[8], red(0x3));
+        assert_eq!(palette.colors[15], red(0x7));
+        assert_eq!(palette.foreground, red(0x5));
+
+        let error = Palette::from_base16_yaml("base00: 000000").unwrap_err();
+        assert_eq!(error.to_string(), "missing color `base08`");
+        let error = Palette::from_base16_yaml("base00: nope").unwrap_err();
+        assert_eq!(error.to_string(), "invalid color `nope` for `base00`");
+    }
+
+    #[test]
+    fn alacritty() {
+        let config = "
+            [window]
+            opacity = 0.9
+
+            [colors.bright]
+            magenta = \"0xff00ff\" # comment
+            [colors]
+            normal.cyan = '#00aaaa'
+        ";
+        let palette = Palette::from_alacritty_toml(config).unwrap();
+        assert_eq!(palette.colors[13], Rgb::new(255, 0, 255));
+        assert_eq!(palette.colors[6], Rgb::new(0, 0xaa, 0xaa));
+        assert_eq!(palette.colors[0], Palette::XTERM.colors[0]);
+        assert!(Palette::from_alacritty_toml("[window]\nopacity = 0.9").is_err());
+    }
+
+    #[test]
+    fn windows_terminal() {
+        let scheme = r##"{"name": "Test \"quoted\"", "white": "#CCCCCC", "brightWhite":"#F2F2F2", "cursorColor": "#FFFFFF", "foreground": "#FF0000"}"##;
+        let palette = Palette::from_windows_terminal_json(scheme).unwrap();
+        assert_eq!(palette.colors[7], Rgb::new(0xcc, 0xcc, 0xcc));
+        assert_eq!(palette.colors[15], Rgb::new(0xf2, 0xf2, 0xf2));
+        assert_eq!(palette.foreground, Rgb::new(255, 0, 0));
+        let error = Palette::from_windows_terminal_json(r##"{"red": "#12"}"##).unwrap_err();
+        assert_eq!(error.key(), Some("red"));
+    }
+
+    #[test]
+    fn windows_terminal_other_values() {
+        let scheme = r##"{"red": 5, "blue": "#0000ff", "green": null, "cyan": ["#00ffff"]}"##;
+        let palette = Palette::from_windows_terminal_json(scheme).unwrap();
+        assert_eq!(palette.colors[1], Palette::XTERM.colors[1]);
+        assert_eq!(palette.colors[4], Rgb::new(0, 0, 255));
+        assert_eq!(palette.colors[2], Palette::XTERM.colors[2]);
+        assert_eq!(palette.colors[6], Palette::XTERM.colors[6]);
+    }
+
+    #[test]
+    fn windows_terminal_nested() {
+        let scheme = r##"{
+            "name": "Outer",
+            "red": "#110000",
+            "profiles": {"defaults": {"foreground": "#ffffff", "red": "#220000"}},
+            "schemes": [{"name": "Inner", "blue": "#000033"}],
+            "background": "#010101"
+        }
+        {"green": "#00ff00"}"##;
+        let palette = Palette::from_windows_terminal_json(scheme).unwrap();
+        assert_eq!(palette.colors[1], Rgb::new(0x11, 0, 0));
+        assert_eq!(palette.colors[4], Palette::XTERM.colors[4]);
+        assert_eq!(palette.colors[2], Palette::XTERM.colors[2]);
+        assert_eq!(palette.foreground, Palette::XTERM.foreground);
+        assert_eq!(palette.background, Rgb::new(1, 1, 1));
+        assert!(Palette::from_windows_terminal_json(r##"[{"red": "#ff0000"}]"##).is_err());
+    }
+
+    #[test]
+    fn iterm2() {
+        let scheme = r#"<plist version="1.0"><dict>
+            <key>Background Color</key>
+            <dict>
+                <key>Alpha Component</key><real>1</real>
+                <key>Blue Component</key><real>1</real>
+                <key>Color Space</key><string>sRGB</string>
+                <key>Green Component</key><real>0.5</real>
+                <key>Red Component</key><real>0</real>
+            </dict>
+            <key>Ansi 15 Color</key>
+            <dict><key>Red Component</key><integer>1</integer></dict>
+            <key>Cursor Color</key>
+            <dict><key>Red Component</key><real>1</real></dict>
+        </dict></plist>"#;
+        let palette = Palette::from_iterm2_plist(scheme).unwrap();
+        assert_eq!(palette.background, Rgb::new(0, 128, 255));
+        assert_eq!(palette.colors[15], Rgb::new(255, 0, 0));
+        assert!(Palette::from_iterm2_plist("<plist><dict></dict></plist>").is_err());
+    }
+
+    #[test]
+    fn kitty() {
+        let theme =
+            "color0 #000001\ncolor15 #fffffe\ncolor16 #123456\ncursor #ffffff\n# color1 #ff0000";
+        let palette = Palette::from_kitty_conf(theme).unwrap();
+        assert_eq!(palette.colors[0], Rgb::new(0, 0, 1));
+        assert_eq!(palette.colors[15], Rgb::new(255, 255, 254));
+        assert_eq!(palette.colors[1], Palette::XTERM.colors[1]);
+        let error = Palette::from_kitty_conf("color3 yellowish").unwrap_err();
+        assert_eq!(error.to_string(), "invalid color `yellowish` for `color3`");
+    }
+}
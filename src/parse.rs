@@ -0,0 +1,569 @@
+use crate::attributes::Attributes;
+use crate::display::{AnsiGenericString, AnsiString, OSControl};
+use crate::style::{Color, Style, UnderlineStyle};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// The escape character that starts every control sequence.
+const ESC: u8 = 0x1B;
+
+/// The bell character, which may also terminate an OSC sequence.
+const BEL: u8 = 0x07;
+
+/// A single piece of input, as split up by [`Tokens`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// A run of text without any escape sequences in it, given as the byte
+    /// range it occupies in the input.
+    Text(Range<usize>),
+
+    /// A Control Sequence Introducer sequence (`ESC [ ... final`), with its
+    /// parameter bytes and final byte.
+    Csi { params: &'a [u8], final_byte: u8 },
+
+    /// An Operating System Command (`ESC ] ... ST`), with its payload.
+    Osc(&'a [u8]),
+
+    /// Any other escape sequence: DCS, SOS, PM and APC strings, or a
+    /// single-character escape.
+    Escape,
+}
+
+/// An iterator that splits bytes into text and escape sequences.
+///
+/// Sequences that are cut off by the end of the input are treated as if they
+/// were terminated there, and a sequence interrupted by a byte that may not
+/// appear in it ends just before that byte. Either way, the ranges of text
+/// tokens always begin and end on ASCII bytes or on the ends of the input, so
+/// they are valid slice ranges of any `str` the bytes came from.
+pub(crate) struct Tokens<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Tokens<'a> {
+        Tokens { bytes, pos: 0 }
+    }
+
+    /// Advances past a string terminated by ST (`ESC \`), or by BEL when
+    /// `allow_bel` is set, and returns the string's contents.
+    fn string_until_st(&mut self, allow_bel: bool) -> &'a [u8] {
+        let start = self.pos;
+        while let Some(&b) = self.bytes.get(self.pos) {
+            if b == BEL && allow_bel {
+                self.pos += 1;
+                return &self.bytes[start..self.pos - 1];
+            }
+            if b == ESC {
+                let end = self.pos;
+                if self.bytes.get(self.pos + 1) == Some(&b'\\') {
+                    self.pos += 2;
+                }
+                return &self.bytes[start..end];
+            }
+            self.pos += 1;
+        }
+        &self.bytes[start..]
+    }
+
+    fn csi(&mut self) -> Token<'a> {
+        let start = self.pos;
+        while let Some(0x30..=0x3F) = self.bytes.get(self.pos) {
+            self.pos += 1;
+        }
+        let params = &self.bytes[start..self.pos];
+        while let Some(0x20..=0x2F) = self.bytes.get(self.pos) {
+            self.pos += 1;
+        }
+        match self.bytes.get(self.pos) {
+            Some(&final_byte @ 0x40..=0x7E) => {
+                self.pos += 1;
+                Token::Csi { params, final_byte }
+            }
+            _ => Token::Escape,
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let start = self.pos;
+        let first = *self.bytes.get(start)?;
+
+        if first != ESC {
+            let len = self.bytes[start..]
+                .iter()
+                .position(|&b| b == ESC)
+                .unwrap_or(self.bytes.len() - start);
+            self.pos += len;
+            return Some(Token::Text(start..self.pos));
+        }
+
+        self.pos += 1;
+        let token = match self.bytes.get(self.pos) {
+            Some(b'[') => {
+                self.pos += 1;
+                self.csi()
+            }
+            Some(b']') => {
+                self.pos += 1;
+                Token::Osc(self.string_until_st(true))
+            }
+            Some(b'P' | b'X' | b'^' | b'_') => {
+                self.pos += 1;
+                self.string_until_st(false);
+                Token::Escape
+            }
+            Some(_) => {
+                while let Some(0x20..=0x2F) = self.bytes.get(self.pos) {
+                    self.pos += 1;
+                }
+                if let Some(0x30..=0x7E) = self.bytes.get(self.pos) {
+                    self.pos += 1;
+                }
+                Token::Escape
+            }
+            None => Token::Escape,
+        };
+        Some(token)
+    }
+}
+
+/// Parses one parameter (or sub-parameter) of a control sequence. Missing
+/// parameters count as zero, as do ones that are too large to be meaningful.
+fn number(param: &[u8]) -> u32 {
+    param
+        .iter()
+        .try_fold(0u32, |acc, &b| match b {
+            b'0'..=b'9' => acc.checked_mul(10)?.checked_add(u32::from(b - b'0')),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+/// Returns the color for an index into the eight standard colors, or their
+/// bright variants.
+pub(crate) fn basic_color(index: u32, bright: bool) -> Color {
+    match (index, bright) {
+        (0, false) => Color::Black,
+        (1, false) => Color::Red,
+        (2, false) => Color::Green,
+        (3, false) => Color::Yellow,
+        (4, false) => Color::Blue,
+        (5, false) => Color::Purple,
+        (6, false) => Color::Cyan,
+        (7, false) => Color::White,
+        (0, true) => Color::DarkGray,
+        (1, true) => Color::LightRed,
+        (2, true) => Color::LightGreen,
+        (3, true) => Color::LightYellow,
+        (4, true) => Color::LightBlue,
+        (5, true) => Color::LightPurple,
+        (6, true) => Color::LightCyan,
+        _ => Color::LightGray,
+    }
+}
+
+/// Reads an extended color (the part after a `38`, `48` or similar) out of
+/// its arguments, which are either the colon-separated sub-parameters of the
+/// same parameter, or the following semicolon-separated parameters.
+fn extended_color<'p, I>(args: &mut I) -> Option<Color>
+where
+    I: Iterator<Item = &'p [u8]>,
+{
+    let mut next = || args.next().map(number);
+    match next()? {
+        5 => Some(Color::Fixed(next()?.min(255) as u8)),
+        2 => {
+            let (r, g, b) = (next()?, next()?, next()?);
+            Some(Color::Rgb(
+                r.min(255) as u8,
+                g.min(255) as u8,
+                b.min(255) as u8,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Applies the parameters of a Select Graphic Rendition sequence (the bytes
+/// between `ESC [` and `m`) to the given style, returning the resulting
+/// style. Unknown parameters are ignored.
+pub(crate) fn apply_sgr(mut style: Style, params: &[u8]) -> Style {
+    let mut groups = params.split(|&b| b == b';');
+
+    while let Some(group) = groups.next() {
+        let mut subparams = group.split(|&b| b == b':');
+        let code = number(subparams.next().unwrap_or_default());
+        let has_subparams = group.contains(&b':');
+
+        match code {
+            0 => style = Style::default(),
+            1 => style.attributes.insert(Attributes::BOLD),
+            2 => style.attributes.insert(Attributes::DIMMED),
+            3 => style.attributes.insert(Attributes::ITALIC),
+            4 if has_subparams => {
+                let kind = number(subparams.next().unwrap_or_default());
+                style.attributes.set(Attributes::UNDERLINE, kind != 0);
+                style.underline_style = match kind {
+                    2 => UnderlineStyle::Double,
+                    3 => UnderlineStyle::Curly,
+                    4 => UnderlineStyle::Dotted,
+                    5 => UnderlineStyle::Dashed,
+                    _ => UnderlineStyle::Single,
+                };
+            }
+            4 => {
+                style.attributes.insert(Attributes::UNDERLINE);
+                style.underline_style = UnderlineStyle::Single;
+            }
+            5 => style.attributes.insert(Attributes::BLINK),
+            6 => style.attributes.insert(Attributes::RAPID_BLINK),
+            7 => style.attributes.insert(Attributes::REVERSE),
+            8 => style.attributes.insert(Attributes::HIDDEN),
+            9 => style.attributes.insert(Attributes::STRIKETHROUGH),
+            21 => {
+                style.attributes.insert(Attributes::UNDERLINE);
+                style.underline_style = UnderlineStyle::Double;
+            }
+            22 => style
+                .attributes
+                .remove(Attributes::BOLD | Attributes::DIMMED),
+            23 => style.attributes.remove(Attributes::ITALIC),
+            24 => {
+                style.attributes.remove(Attributes::UNDERLINE);
+                style.underline_style = UnderlineStyle::Single;
+            }
+            25 => style
+                .attributes
+                .remove(Attributes::BLINK | Attributes::RAPID_BLINK),
+            27 => style.attributes.remove(Attributes::REVERSE),
+            28 => style.attributes.remove(Attributes::HIDDEN),
+            29 => style.attributes.remove(Attributes::STRIKETHROUGH),
+            51 => style.attributes.insert(Attributes::FRAMED),
+            52 => style.attributes.insert(Attributes::ENCIRCLED),
+            53 => style.attributes.insert(Attributes::OVERLINE),
+            54 => style
+                .attributes
+                .remove(Attributes::FRAMED | Attributes::ENCIRCLED),
+            55 => style.attributes.remove(Attributes::OVERLINE),
+            73 => style.attributes.insert(Attributes::SUPERSCRIPT),
+            74 => style.attributes.insert(Attributes::SUBSCRIPT),
+            75 => style
+                .attributes
+                .remove(Attributes::SUPERSCRIPT | Attributes::SUBSCRIPT),
+            30..=37 => style.foreground = Some(basic_color(code - 30, false)),
+            38 | 48 | 58 => {
+                let color = if has_subparams {
+                    // The colon form of a direct color may include a color
+                    // space identifier, as in `38:2::r:g:b`.
+                    let rest: Vec<&[u8]> = subparams.collect();
+                    match rest.as_slice() {
+                        [two, _, r, g, b, ..] if number(two) == 2 => {
+                            extended_color(&mut [*two, *r, *g, *b].into_iter())
+                        }
+                        _ => extended_color(&mut rest.into_iter()),
+                    }
+                } else {
+                    extended_color(&mut groups)
+                };
+                match code {
+                    38 => style.foreground = color.or(style.foreground),
+                    48 => style.background = color.or(style.background),
+                    _ => style.underline_color = color.or(style.underline_color),
+                }
+            }
+            39 => style.foreground = Some(Color::Default),
+            40..=47 => style.background = Some(basic_color(code - 40, false)),
+            49 => style.background = Some(Color::Default),
+            59 => style.underline_color = Some(Color::Default),
+            90..=97 => style.foreground = Some(basic_color(code - 90, true)),
+            100..=107 => style.background = Some(basic_color(code - 100, true)),
+            _ => {}
+        }
+    }
+
+    style
+}
+
+/// Parses text containing ANSI escape sequences back into a list of
+/// `AnsiString`s, each with the `Style` that was in effect for it.
+///
+/// This is the inverse of the `Display` implementation of `AnsiStrings`:
+/// SGR sequences set the style of the text that follows them, OSC 8
+/// hyperlinks are attached to the text they surround, and OSC 2 title
+/// sequences become [`AnsiGenericString::title`] values. Any other escape
+/// sequences are dropped.
+///
+/// Neighbouring text that ends up with the same style and hyperlink is
+/// merged into a single `AnsiString`, so the result may have fewer pieces
+/// than the list that produced the input, but it displays the same way.
+/// A reset immediately followed by a new style is read as that style, not
+/// as one with [`Style::reset_before_style`] set.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{parse_ansi, AnsiStrings, Color::Red};
+///
+/// let strings = [Red.paint("red"), Red.bold().paint(" and bold")];
+/// let text = AnsiStrings(&strings).to_string();
+///
+/// let parsed = parse_ansi(&text);
+/// assert_eq!(parsed, strings);
+/// assert_eq!(AnsiStrings(&parsed).to_string(), text);
+/// ```
+pub fn parse_ansi(input: &str) -> Vec<AnsiString<'static>> {
+    let mut strings = Vec::new();
+    let mut style = Style::default();
+    let mut link: Option<String> = None;
+    let mut text = String::new();
+
+    let flush = |strings: &mut Vec<AnsiString<'static>>,
+                 text: &mut String,
+                 style: Style,
+                 link: &Option<String>| {
+        if !text.is_empty() {
+            strings.push(AnsiGenericString {
+                style,
+                string: Cow::Owned(std::mem::take(text)),
+                oscontrol: link.clone().map(|url| OSControl::Link {
+                    url: Cow::Owned(url),
+                }),
+            });
+        }
+    };
+
+    for token in Tokens::new(input.as_bytes()) {
+        match token {
+            Token::Text(range) => text.push_str(&input[range]),
+            Token::Csi {
+                params,
+                final_byte: b'm',
+            } => {
+                let next = apply_sgr(style, params);
+                if next != style {
+                    flush(&mut strings, &mut text, style, &link);
+                    style = next;
+                }
+            }
+            Token::Osc(payload) => {
+                let payload = String::from_utf8_lossy(payload);
+                if let Some(rest) = payload.strip_prefix("8;") {
+                    // The hyperlink parameters come before the URL, and
+                    // are separated from it by a semicolon.
+                    let url = rest.split_once(';').map_or("", |(_, url)| url);
+                    let next = if url.is_empty() {
+                        None
+                    } else {
+                        Some(url.to_owned())
+                    };
+                    if next != link {
+                        flush(&mut strings, &mut text, style, &link);
+                        link = next;
+                    }
+                } else if let Some(title) = payload.strip_prefix("2;") {
+                    flush(&mut strings, &mut text, style, &link);
+                    strings.push(AnsiGenericString::title(title.to_owned()));
+                }
+            }
+            Token::Csi { .. } | Token::Escape => {}
+        }
+    }
+
+    flush(&mut strings, &mut text, style, &link);
+    strings
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::display::AnsiStrings;
+    use crate::style::Color::*;
+
+    fn round_trip(strings: &[AnsiString<'static>]) {
+        let text = AnsiStrings(strings).to_string();
+        let parsed = parse_ansi(&text);
+        assert_eq!(parsed, strings, "parsing {:?}", text);
+        assert_eq!(AnsiStrings(&parsed).to_string(), text);
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(parse_ansi("hello"), [AnsiString::from("hello")]);
+        assert_eq!(parse_ansi(""), []);
+    }
+
+    #[test]
+    fn styles() {
+        round_trip(&[Red.paint("red")]);
+        round_trip(&[Red.bold().underline().on(White).paint("busy")]);
+        round_trip(&[Style::new()
+            .bold()
+            .dimmed()
+            .italic()
+            .underline()
+            .blink()
+            .reverse()
+            .hidden()
+            .strikethrough()
+            .paint("everything")]);
+        round_trip(&[LightPurple.on(DarkGray).paint("bright")]);
+        round_trip(&[Default.on(Default).paint("default")]);
+    }
+
+    #[test]
+    fn extended_colors() {
+        round_trip(&[Fixed(100).on(Fixed(200)).paint("fixed")]);
+        round_trip(&[Rgb(70, 130, 180).on(Rgb(5, 10, 15)).paint("rgb")]);
+        round_trip(&[Blue.on(Rgb(70, 130, 180)).paint("mixed")]);
+    }
+
+    #[test]
+    fn sequences() {
+        round_trip(&[
+            Red.paint("["),
+            Red.bold().paint("101010"),
+            Red.paint("]"),
+            Style::default().paint(" plain "),
+            Green.paint("green"),
+        ]);
+    }
+
+    #[test]
+    fn hyperlinks() {
+        round_trip(&[
+            Green.paint("Before link. "),
+            Blue.underline()
+                .paint("Link to example.com.")
+                .hyperlink("https://example.com"),
+            Green.paint(" After link."),
+        ]);
+        round_trip(&[Style::default()
+            .paint("bare")
+            .hyperlink("https://example.com")]);
+    }
+
+    #[test]
+    fn titles() {
+        round_trip(&[
+            AnsiGenericString::title("Test Title"),
+            Red.paint("after the title"),
+        ]);
+    }
+
+    #[test]
+    fn colon_colors() {
+        assert_eq!(
+            parse_ansi("\x1B[38:2::1:2:3;48:5:42mx"),
+            [Rgb(1, 2, 3).on(Fixed(42)).paint("x")]
+        );
+        assert_eq!(parse_ansi("\x1B[38:2:1:2:3mx"), [Rgb(1, 2, 3).paint("x")]);
+    }
+
+    #[test]
+    fn attributes_off() {
+        assert_eq!(
+            parse_ansi("\x1B[1;2;3mab\x1B[22mcd\x1B[23;39mef"),
+            [
+                Style::new().bold().dimmed().italic().paint("ab"),
+                Style::new().italic().paint("cd"),
+                Default.normal().paint("ef"),
+            ]
+        );
+    }
+
+    #[test]
+    fn underline_styles() {
+        round_trip(&[
+            Red.underline_style(UnderlineStyle::Curly).paint("curly"),
+            Red.underline_style(UnderlineStyle::Dashed).paint("dashed"),
+            Red.underline().paint("single"),
+        ]);
+        assert_eq!(
+            parse_ansi("\x1B[4:2ma\x1B[4:0mb"),
+            [
+                Style::new()
+                    .underline_style(UnderlineStyle::Double)
+                    .paint("a"),
+                AnsiString::from("b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn underline_colors() {
+        round_trip(&[
+            Style::new()
+                .underline()
+                .underline_color(Fixed(100))
+                .paint("a"),
+            Style::new()
+                .underline()
+                .underline_color(Rgb(1, 2, 3))
+                .paint("b"),
+            Style::new().underline_color(Default).paint("c"),
+        ]);
+        assert_eq!(
+            parse_ansi("\x1B[4;58;5;1ma"),
+            [Style::new()
+                .underline()
+                .underline_color(Fixed(1))
+                .paint("a")]
+        );
+    }
+
+    #[test]
+    fn other_attributes() {
+        round_trip(&[Style::new()
+            .rapid_blink()
+            .framed()
+            .encircled()
+            .overline()
+            .superscript()
+            .double_underline()
+            .paint("all")]);
+        assert_eq!(
+            parse_ansi("\x1B[4:2;6;51;74ma\x1B[25;54;75mb"),
+            [
+                Style::new()
+                    .double_underline()
+                    .rapid_blink()
+                    .framed()
+                    .subscript()
+                    .paint("a"),
+                Style::new().double_underline().paint("b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_padding() {
+        assert_eq!(
+            parse_ansi("\x1B[01;04;34mx"),
+            [Blue.bold().underline().paint("x")]
+        );
+    }
+
+    #[test]
+    fn other_sequences_are_dropped() {
+        assert_eq!(
+            parse_ansi("a\x1B[2Jb\x1B(Bc\x1BP1$r\x1B\\d\x1B]0;icon\x07e\x1B"),
+            [AnsiString::from("abcde")]
+        );
+    }
+
+    #[test]
+    fn malformed_sequences_do_not_panic() {
+        assert_eq!(parse_ansi("\x1B[31é"), [AnsiString::from("é")]);
+        assert_eq!(parse_ansi("\x1B]8;;unterminated"), []);
+        assert_eq!(
+            parse_ansi("\x1B[38;5m\x1B[38;2;1mx"),
+            [AnsiString::from("x")]
+        );
+        assert_eq!(parse_ansi("\x1B[99999999999mx"), [AnsiString::from("x")]);
+    }
+}
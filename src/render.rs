@@ -0,0 +1,425 @@
+use crate::ansi::{Infix, Prefix, Suffix};
+use crate::display::{AnsiByteString, AnsiByteStrings, AnsiGenericString, AnsiGenericStrings};
+use crate::display::{AnsiString, AnsiStrings};
+use crate::style::{Color, Style, UnderlineStyle};
+use crate::terminal::Capabilities;
+use std::fmt;
+use std::io;
+
+/// The range of colors that output is being written for.
+///
+/// Terminals differ in how many colors they can show, and output that goes
+/// to a file or a pipe may not want any at all. Rendering a value for a
+/// particular depth replaces the colors it can’t show with the closest ones
+/// it can, so the same styled value can be written anywhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// No colors or styles at all. Text is written without any escape
+    /// sequences, as it would be to a plain log file.
+    NoColor,
+
+    /// The sixteen basic colors, foreground codes `30` to `37` and `90` to
+    /// `97`, and background codes `40` to `47` and `100` to `107`.
+    Ansi16,
+
+    /// The 256 colors of the xterm palette, as `Fixed` colors.
+    Ansi256,
+
+    /// Any 24-bit `Rgb` color.
+    TrueColor,
+}
+
+impl Style {
+    /// Returns a `Style` that can be displayed at the given color depth.
+    ///
+    /// Colors the depth can’t show are replaced with the closest ones it can,
+    /// using [`Color::to_ansi256`] and [`Color::to_ansi16`]. For
+    /// `ColorDepth::NoColor`, every property is removed, leaving a plain
+    /// style.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, ColorDepth, Style};
+    ///
+    /// let style = Color::Rgb(255, 135, 0).bold();
+    /// assert_eq!(style.to_depth(ColorDepth::Ansi256), Color::Fixed(208).bold());
+    /// assert_eq!(style.to_depth(ColorDepth::Ansi16), Color::Yellow.bold());
+    /// assert_eq!(style.to_depth(ColorDepth::NoColor), Style::new());
+    /// ```
+    pub fn to_depth(self, depth: ColorDepth) -> Style {
+        match depth {
+            ColorDepth::NoColor => Style::default(),
+            ColorDepth::Ansi16 => Style {
+                foreground: self.foreground.map(Color::to_ansi16),
+                background: self.background.map(Color::to_ansi16),
+                underline_color: self.underline_color.map(Color::to_ansi16),
+                ..self
+            },
+            ColorDepth::Ansi256 => Style {
+                foreground: self.foreground.map(Color::to_ansi256),
+                background: self.background.map(Color::to_ansi256),
+                underline_color: self.underline_color.map(Color::to_ansi256),
+                ..self
+            },
+            ColorDepth::TrueColor => self,
+        }
+    }
+
+    /// Returns a `Style` that only uses what the given capabilities allow.
+    pub(crate) fn to_capabilities(self, capabilities: Capabilities) -> Style {
+        let mut style = self.to_depth(capabilities.color_depth);
+        if !capabilities.styled_underlines {
+            style.underline_style = UnderlineStyle::Single;
+            style.underline_color = None;
+        }
+        style
+    }
+}
+
+/// A value to be written for particular [`Capabilities`], rather than as it
+/// is. This is returned by the `render` methods of the other types in this
+/// crate, and writes them in the same way, except with their styles changed
+/// by [`Style::to_depth`], other kinds of underline drawn as single lines,
+/// and hyperlinks or titles left out, when they aren’t supported.
+///
+/// A `ColorDepth` can be given in place of `Capabilities`. For
+/// `ColorDepth::NoColor`, hyperlinks and titles are left out as well, so that
+/// nothing but the text itself is written.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{ColorDepth, Color::Rgb};
+///
+/// let orange = Rgb(255, 135, 0).paint("orange");
+/// assert_eq!(orange.render(ColorDepth::TrueColor).to_string(), "\x1b[38;2;255;135;0morange\x1b[0m");
+/// assert_eq!(orange.render(ColorDepth::Ansi256).to_string(), "\x1b[38;5;208morange\x1b[0m");
+/// assert_eq!(orange.render(ColorDepth::NoColor).to_string(), "orange");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Rendered<T> {
+    value: T,
+    capabilities: Capabilities,
+}
+
+impl<T> Rendered<T> {
+    fn new(value: T, capabilities: Capabilities) -> Rendered<T> {
+        Rendered {
+            value,
+            capabilities,
+        }
+    }
+}
+
+impl<'a, S: 'a + ToOwned + ?Sized> AnsiGenericString<'a, S>
+where
+    <S as ToOwned>::Owned: fmt::Debug,
+{
+    /// Render this string for the given capabilities. See [`Rendered`].
+    pub fn render<C: Into<Capabilities>>(&self, capabilities: C) -> Rendered<&Self> {
+        Rendered::new(self, capabilities.into())
+    }
+}
+
+impl<'a, S: 'a + ToOwned + ?Sized + PartialEq> AnsiGenericStrings<'a, S>
+where
+    <S as ToOwned>::Owned: fmt::Debug,
+{
+    /// Render these strings for the given capabilities. See [`Rendered`].
+    pub fn render<C: Into<Capabilities>>(&self, capabilities: C) -> Rendered<&Self> {
+        Rendered::new(self, capabilities.into())
+    }
+}
+
+impl Prefix {
+    /// Render this prefix for the given capabilities. See [`Rendered`].
+    pub fn render<C: Into<Capabilities>>(self, capabilities: C) -> Rendered<Self> {
+        Rendered::new(self, capabilities.into())
+    }
+}
+
+impl Infix {
+    /// Render this infix for the given capabilities. See [`Rendered`].
+    pub fn render<C: Into<Capabilities>>(self, capabilities: C) -> Rendered<Self> {
+        Rendered::new(self, capabilities.into())
+    }
+}
+
+impl Suffix {
+    /// Render this suffix for the given capabilities. See [`Rendered`].
+    pub fn render<C: Into<Capabilities>>(self, capabilities: C) -> Rendered<Self> {
+        Rendered::new(self, capabilities.into())
+    }
+}
+
+impl<'a> fmt::Display for Rendered<&AnsiString<'a>> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let w: &mut dyn fmt::Write = f;
+        self.value.write_to_any(w, self.capabilities)
+    }
+}
+
+impl<'a> fmt::Display for Rendered<&AnsiStrings<'a>> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let w: &mut dyn fmt::Write = f;
+        self.value.write_to_any(w, self.capabilities)
+    }
+}
+
+impl<'a> Rendered<&AnsiByteString<'a>> {
+    /// Write the `AnsiByteString` to an `io::Write`, for the capabilities it
+    /// was rendered for.
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let w: &mut dyn io::Write = w;
+        self.value.write_to_any(w, self.capabilities)
+    }
+}
+
+impl<'a> Rendered<&AnsiByteStrings<'a>> {
+    /// Write the `AnsiByteStrings` to an `io::Write`, for the capabilities
+    /// they were rendered for.
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let w: &mut dyn io::Write = w;
+        self.value.write_to_any(w, self.capabilities)
+    }
+}
+
+impl fmt::Display for Rendered<Prefix> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let f: &mut dyn fmt::Write = f;
+        self.value
+            .0
+            .to_capabilities(self.capabilities)
+            .write_prefix(f)
+    }
+}
+
+impl fmt::Display for Rendered<Infix> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Infix(first, next) = self.value;
+        let f: &mut dyn fmt::Write = f;
+        let infix = Infix(
+            first.to_capabilities(self.capabilities),
+            next.to_capabilities(self.capabilities),
+        );
+        infix.write_to_any(f, self.capabilities.transitions)
+    }
+}
+
+impl fmt::Display for Rendered<Suffix> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let f: &mut dyn fmt::Write = f;
+        self.value
+            .0
+            .to_capabilities(self.capabilities)
+            .write_suffix(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ColorDepth::*;
+    use crate::display::{AnsiGenericString, AnsiStrings};
+    use crate::style::Color::*;
+    use crate::style::{Style, UnderlineStyle};
+    use crate::terminal::{Capabilities, Transitions};
+
+    #[test]
+    fn strings() {
+        let l = [
+            Rgb(255, 0, 0).bold().paint("red"),
+            Rgb(250, 5, 5).bold().paint(" more red"),
+            Fixed(19)
+                .on(Rgb(1, 2, 3))
+                .paint(" blue")
+                .hyperlink("https://example.com"),
+        ];
+        let a = AnsiStrings(&l);
+        assert_eq!(a.render(TrueColor).to_string(), a.to_string());
+        assert_eq!(
+            a.render(Ansi256).to_string(),
+            AnsiStrings(&[
+                Fixed(196).bold().paint("red more red"),
+                Fixed(19)
+                    .on(Fixed(16))
+                    .paint(" blue")
+                    .hyperlink("https://example.com"),
+            ])
+            .to_string()
+        );
+        assert_eq!(
+            a.render(Ansi16).to_string(),
+            AnsiStrings(&[
+                LightRed.bold().paint("red more red"),
+                Blue.on(Black)
+                    .paint(" blue")
+                    .hyperlink("https://example.com"),
+            ])
+            .to_string()
+        );
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(
+            a.render(Ansi256).to_string(),
+            "\x1B[1;38;5;196mred more red\x1B[0m\x1B[48;5;16;38;5;19m\x1B]8;;https://example.com\x1B\\ blue\x1B]8;;\x1B\\\x1B[0m"
+        );
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(
+            a.render(Ansi16).to_string(),
+            "\x1B[1;91mred more red\x1B[0m\x1B[40;34m\x1B]8;;https://example.com\x1B\\ blue\x1B]8;;\x1B\\\x1B[0m"
+        );
+        assert_eq!(a.render(NoColor).to_string(), "red more red blue");
+    }
+
+    #[test]
+    fn titles() {
+        let title = AnsiGenericString::title("Title");
+        assert_eq!(title.render(Ansi16).to_string(), "\x1B]2;Title\x1B\\");
+        assert_eq!(title.render(NoColor).to_string(), "");
+    }
+
+    #[test]
+    fn capabilities() {
+        let caps = Capabilities {
+            color_depth: Ansi16,
+            hyperlinks: false,
+            titles: true,
+            transitions: Transitions::Reset,
+            styled_underlines: false,
+        };
+        let l = [
+            AnsiGenericString::title("Title"),
+            Red.paint("link").hyperlink("https://example.com"),
+        ];
+        assert_eq!(
+            AnsiStrings(&l).render(caps).to_string(),
+            "\x1B]2;Title\x1B\\\x1B[31mlink\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn bytes() {
+        let mut v = Vec::new();
+        Rgb(0, 0, 0)
+            .paint(&b"black"[..])
+            .render(Ansi256)
+            .write_to(&mut v)
+            .unwrap();
+        assert_eq!(v, b"\x1B[38;5;16mblack\x1B[0m");
+    }
+
+    #[test]
+    fn prefix_infix_suffix() {
+        let style = Rgb(0, 0, 0).on(Rgb(255, 255, 255));
+        assert_eq!(
+            style.prefix().render(Ansi16).to_string(),
+            Black.on(LightGray).prefix().to_string()
+        );
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(style.prefix().render(Ansi16).to_string(), "\x1B[107;30m");
+        assert_eq!(style.prefix().render(NoColor).to_string(), "");
+        assert_eq!(style.suffix().render(NoColor).to_string(), "");
+        assert_eq!(style.suffix().render(Ansi256).to_string(), "\x1B[0m");
+        assert_eq!(
+            Rgb(0, 0, 0)
+                .normal()
+                .infix(Rgb(1, 1, 1).normal())
+                .render(Ansi256)
+                .to_string(),
+            ""
+        );
+        assert_eq!(
+            Style::new()
+                .infix(Rgb(0, 0, 0).normal())
+                .render(NoColor)
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn styled_underlines() {
+        let curly = Red.underline_style(UnderlineStyle::Curly).paint("x");
+        let caps = Capabilities {
+            styled_underlines: false,
+            ..Ansi16.into()
+        };
+        assert_eq!(curly.render(Ansi16).to_string(), curly.to_string());
+        assert_eq!(
+            curly.render(caps).to_string(),
+            Red.underline().paint("x").to_string()
+        );
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(curly.render(Ansi16).to_string(), "\x1B[4:3;31mx\x1B[0m");
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(curly.render(caps).to_string(), "\x1B[4;31mx\x1B[0m");
+
+        let colored = Style::new().underline().underline_color(Rgb(255, 0, 0));
+        assert_eq!(
+            colored.paint("x").render(Ansi256).to_string(),
+            Style::new()
+                .underline()
+                .underline_color(Fixed(196))
+                .paint("x")
+                .to_string()
+        );
+        assert_eq!(
+            colored.paint("x").render(caps).to_string(),
+            Style::new().underline().paint("x").to_string()
+        );
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(
+            colored.paint("x").render(Ansi256).to_string(),
+            "\x1B[4;58;5;196mx\x1B[0m"
+        );
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(
+            colored.paint("x").render(caps).to_string(),
+            "\x1B[4mx\x1B[0m"
+        );
+        assert_eq!(
+            Style::new()
+                .underline()
+                .infix(Style::new().underline_style(UnderlineStyle::Dotted))
+                .render(caps)
+                .to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn minimal_transitions() {
+        let caps = Capabilities {
+            transitions: Transitions::Minimal,
+            ..Ansi256.into()
+        };
+        let l = [
+            Red.bold().underline().paint("a"),
+            Red.paint("b"),
+            Style::new().on(Blue).paint("c"),
+            Style::new().dimmed().paint("d"),
+        ];
+        let rendered = AnsiStrings(&l).render(caps).to_string();
+        assert_eq!(crate::util::strip_ansi(&rendered), "abcd");
+        assert!(rendered.len() < AnsiStrings(&l).render(Ansi256).to_string().len());
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(
+            rendered,
+            "\x1B[1;4;31ma\x1B[22;24mb\x1B[39;44mc\x1B[49;2md\x1B[0m"
+        );
+        assert_eq!(
+            Red.bold().infix(Red.normal()).render(caps).to_string(),
+            "\x1B[22m"
+        );
+        #[cfg(not(feature = "gnu_legacy"))]
+        assert_eq!(
+            Style::new()
+                .bold()
+                .dimmed()
+                .infix(Style::new().dimmed())
+                .render(caps)
+                .to_string(),
+            "\x1B[22;2m"
+        );
+    }
+}
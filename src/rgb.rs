@@ -23,6 +23,11 @@ impl Rgb {
         Self::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
     }
 
+    /// Creates a new [Rgb] color from a string of the form `0xRRGGBB`,
+    /// giving black for anything else.
+    ///
+    /// Parse the string instead to accept more forms and get an error for
+    /// invalid input.
     pub fn from_hex_string(hex: String) -> Self {
         if hex.chars().count() == 8 && hex.starts_with("0x") {
             // eprintln!("hex:{:?}", hex);
@@ -66,7 +71,7 @@ impl Rgb {
         Self::from_f32(x, x, x)
     }
 
-    /// Creates a new [Rgb] color from a [HSL] color
+    // Creates a new [Rgb] color from a [HSL] color
     // pub fn from_hsl(hsl: HSL) -> Self {
     //     if hsl.s == 0.0 {
     //         return Self::gray_f32(hsl.l);
@@ -0,0 +1,452 @@
+//! A human-readable serde format for colors and styles, for use with
+//! `#[serde(with = "...")]` on the fields of a configuration struct.
+//!
+//! Colors are written as names, palette indices or hex strings: `"red"`,
+//! `208` or `"#7b7b7b"`. Styles are written as the strings that
+//! [`Style`]’s `FromStr` implementation reads, such as
+//! `"bold red on #202020"`.
+//!
+//! When reading, colors can be anything that [`Color`]’s `FromStr`
+//! implementation reads, and styles can also be objects that only name the
+//! properties that are set, such as `{"fg": "red", "bold": true}`. The
+//! format used by the derived implementations, such as `{"Rgb":[1,2,3]}` for
+//! a color, is still accepted, so existing files keep working.
+//!
+//! # Examples
+//!
+//! ```
+//! use nu_ansi_term::{Color, Style};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Theme {
+//!     #[serde(with = "nu_ansi_term::serde_human::style")]
+//!     header: Style,
+//!     #[serde(with = "nu_ansi_term::serde_human::color")]
+//!     accent: Color,
+//!     #[serde(with = "nu_ansi_term::serde_human::option_style", default)]
+//!     highlight: Option<Style>,
+//! }
+//!
+//! let theme = Theme {
+//!     header: Color::Red.bold(),
+//!     accent: Color::Fixed(208),
+//!     highlight: None,
+//! };
+//! let json = serde_json::to_string(&theme).unwrap();
+//! assert_eq!(json, r#"{"header":"bold red","accent":208,"highlight":null}"#);
+//!
+//! let edited = r##"{"header": {"fg": "red", "bold": true}, "accent": "#ff8700"}"##;
+//! let theme: Theme = serde_json::from_str(edited).unwrap();
+//! assert_eq!(theme.header, Color::Red.bold());
+//! assert_eq!(theme.accent, Color::Rgb(255, 135, 0));
+//! ```
+
+use crate::attributes::Attributes;
+use crate::color_spec::ColorSpec;
+use crate::spec::parse_underline_style;
+use crate::style::{Color, Style, UnderlineStyle};
+use serde::de::value::{EnumAccessDeserializer, MapAccessDeserializer};
+use serde::de::{self, Deserializer, EnumAccess, MapAccess, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Serializes a color as a name, a palette index or a hex string, and
+/// deserializes any of those or the derived format.
+pub mod color {
+    use super::HumanColor;
+    use crate::style::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes a color as a name, a palette index or a hex string.
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        HumanColor(*color).serialize(serializer)
+    }
+
+    /// Deserializes a color from a name, a palette index, any other string
+    /// that `Color` can be parsed from, or the derived format.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        HumanColor::deserialize(deserializer).map(|color| color.0)
+    }
+}
+
+/// Like [`color`], for an optional color, which is `null` when missing.
+pub mod option_color {
+    use super::HumanColor;
+    use crate::style::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes an optional color, writing `null` when it is missing.
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.map(HumanColor).serialize(serializer)
+    }
+
+    /// Deserializes an optional color.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        Option::<HumanColor>::deserialize(deserializer).map(|color| color.map(|c| c.0))
+    }
+}
+
+/// Serializes a style as a string such as `"bold red on blue"`, and
+/// deserializes that, an object with only the properties that are set, or
+/// the derived format.
+pub mod style {
+    use super::HumanStyle;
+    use crate::style::Style;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes a style as a string such as `"bold red on blue"`.
+    pub fn serialize<S: Serializer>(style: &Style, serializer: S) -> Result<S::Ok, S::Error> {
+        HumanStyle(*style).serialize(serializer)
+    }
+
+    /// Deserializes a style from a string, an object with only the
+    /// properties that are set, or the derived format.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Style, D::Error> {
+        HumanStyle::deserialize(deserializer).map(|style| style.0)
+    }
+}
+
+/// Like [`style`], for an optional style, which is `null` when missing.
+pub mod option_style {
+    use super::HumanStyle;
+    use crate::style::Style;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes an optional style, writing `null` when it is missing.
+    pub fn serialize<S: Serializer>(
+        style: &Option<Style>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        style.map(HumanStyle).serialize(serializer)
+    }
+
+    /// Deserializes an optional style.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Style>, D::Error> {
+        Option::<HumanStyle>::deserialize(deserializer).map(|style| style.map(|s| s.0))
+    }
+}
+
+/// A color in the human-readable format.
+#[derive(Clone, Copy)]
+pub(crate) struct HumanColor(pub(crate) Color);
+
+impl Serialize for HumanColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Color::Fixed(n) => serializer.serialize_u8(n),
+            color => serializer.collect_str(&ColorSpec(color)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HumanColor, D::Error> {
+        deserializer.deserialize_any(ColorVisitor).map(HumanColor)
+    }
+}
+
+struct ColorVisitor;
+
+impl<'de> Visitor<'de> for ColorVisitor {
+    type Value = Color;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a color name, a palette index from 0 to 255, or a hex color")
+    }
+
+    fn visit_u64<E: de::Error>(self, n: u64) -> Result<Color, E> {
+        u8::try_from(n)
+            .map(Color::Fixed)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(n), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, n: i64) -> Result<Color, E> {
+        u8::try_from(n)
+            .map(Color::Fixed)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(n), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Color, E> {
+        s.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Color, A::Error> {
+        Color::deserialize(MapAccessDeserializer::new(map))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Color, A::Error> {
+        Color::deserialize(EnumAccessDeserializer::new(data))
+    }
+}
+
+/// A style in the human-readable format.
+#[derive(Clone, Copy)]
+pub(crate) struct HumanStyle(pub(crate) Style);
+
+impl Serialize for HumanStyle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanStyle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HumanStyle, D::Error> {
+        deserializer.deserialize_any(StyleVisitor).map(HumanStyle)
+    }
+}
+
+struct StyleVisitor;
+
+impl<'de> Visitor<'de> for StyleVisitor {
+    type Value = Style;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a style such as \"bold red on blue\", or an object")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Style, E> {
+        s.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Style, A::Error> {
+        StyleObject::deserialize(MapAccessDeserializer::new(map)).map(Style::from)
+    }
+}
+
+/// A style written as an object. Every field is optional, and both the
+/// short names and the ones used by the derived format are accepted.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StyleObject {
+    #[serde(default, alias = "fg", with = "option_color")]
+    foreground: Option<Color>,
+    #[serde(default, alias = "bg", with = "option_color")]
+    background: Option<Color>,
+    #[serde(default, with = "option_color")]
+    underline_color: Option<Color>,
+    #[serde(default, alias = "is_bold")]
+    bold: bool,
+    #[serde(default, alias = "is_dimmed")]
+    dimmed: bool,
+    #[serde(default, alias = "is_italic")]
+    italic: bool,
+    #[serde(default, alias = "is_underline")]
+    underline: bool,
+    #[serde(default, deserialize_with = "underline_style")]
+    underline_style: UnderlineStyle,
+    #[serde(default, alias = "is_blink")]
+    blink: bool,
+    #[serde(default, alias = "is_rapid_blink")]
+    rapid_blink: bool,
+    #[serde(default, alias = "is_reverse")]
+    reverse: bool,
+    #[serde(default, alias = "is_hidden")]
+    hidden: bool,
+    #[serde(default, alias = "is_strikethrough")]
+    strikethrough: bool,
+    #[serde(default, alias = "is_framed")]
+    framed: bool,
+    #[serde(default, alias = "is_encircled")]
+    encircled: bool,
+    #[serde(default, alias = "is_overline")]
+    overline: bool,
+    #[serde(default, alias = "is_superscript")]
+    superscript: bool,
+    #[serde(default, alias = "is_subscript")]
+    subscript: bool,
+    #[serde(default)]
+    prefix_with_reset: bool,
+}
+
+/// The keys that [`StyleObject`] reads, including their aliases, so that a
+/// theme can tell when a group of names has been written as a style.
+pub(crate) const STYLE_FIELDS: [&str; 35] = [
+    "foreground",
+    "fg",
+    "background",
+    "bg",
+    "underline_color",
+    "bold",
+    "is_bold",
+    "dimmed",
+    "is_dimmed",
+    "italic",
+    "is_italic",
+    "underline",
+    "is_underline",
+    "underline_style",
+    "blink",
+    "is_blink",
+    "rapid_blink",
+    "is_rapid_blink",
+    "reverse",
+    "is_reverse",
+    "hidden",
+    "is_hidden",
+    "strikethrough",
+    "is_strikethrough",
+    "framed",
+    "is_framed",
+    "encircled",
+    "is_encircled",
+    "overline",
+    "is_overline",
+    "superscript",
+    "is_superscript",
+    "subscript",
+    "is_subscript",
+    "prefix_with_reset",
+];
+
+impl From<StyleObject> for Style {
+    fn from(object: StyleObject) -> Style {
+        let mut attributes = Attributes::empty();
+        for (attribute, set) in [
+            (Attributes::BOLD, object.bold),
+            (Attributes::DIMMED, object.dimmed),
+            (Attributes::ITALIC, object.italic),
+            (Attributes::UNDERLINE, object.underline),
+            (Attributes::BLINK, object.blink),
+            (Attributes::RAPID_BLINK, object.rapid_blink),
+            (Attributes::REVERSE, object.reverse),
+            (Attributes::HIDDEN, object.hidden),
+            (Attributes::STRIKETHROUGH, object.strikethrough),
+            (Attributes::FRAMED, object.framed),
+            (Attributes::ENCIRCLED, object.encircled),
+            (Attributes::OVERLINE, object.overline),
+            (Attributes::SUPERSCRIPT, object.superscript),
+            (Attributes::SUBSCRIPT, object.subscript),
+        ] {
+            attributes.set(attribute, set);
+        }
+
+        Style {
+            foreground: object.foreground,
+            background: object.background,
+            underline_color: object.underline_color,
+            attributes,
+            underline_style: object.underline_style,
+            prefix_with_reset: object.prefix_with_reset,
+        }
+    }
+}
+
+/// Reads the kind of underline by name, ignoring case, so that both the
+/// derived `"Curly"` and a hand-written `"curly"` are accepted.
+fn underline_style<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UnderlineStyle, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    parse_underline_style(&name).ok_or_else(|| {
+        de::Error::unknown_variant(&name, &["single", "double", "curly", "dotted", "dashed"])
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::style::Color::{self, *};
+    use crate::style::{Style, UnderlineStyle};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config {
+        #[serde(with = "super::color")]
+        color: Color,
+        #[serde(with = "super::style")]
+        style: Style,
+    }
+
+    fn config(color: &str, style: &str) -> Config {
+        let json = format!(r#"{{"color":{},"style":{}}}"#, color, style);
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn serialize() {
+        let written = |color, style| serde_json::to_string(&Config { color, style }).unwrap();
+        assert_eq!(
+            written(Red, Style::new()),
+            r#"{"color":"red","style":"normal"}"#
+        );
+        assert_eq!(
+            written(Rgb(123, 123, 123), LightBlue.on(Fixed(236)).italic()),
+            r##"{"color":"#7b7b7b","style":"italic light_blue on 236"}"##
+        );
+        assert_eq!(
+            written(
+                Fixed(208),
+                Style::new().underline_style(UnderlineStyle::Dotted)
+            ),
+            r#"{"color":208,"style":"underline=dotted"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        assert_eq!(
+            config(r#""Bright Red""#, r##""bold fg=#202020""##),
+            Config {
+                color: LightRed,
+                style: Rgb(32, 32, 32).bold()
+            }
+        );
+        assert_eq!(
+            config(
+                "7",
+                r#"{"bg": "blue", "underline": true, "underline_style": "curly"}"#
+            ),
+            Config {
+                color: Fixed(7),
+                style: Style::new().on(Blue).underline_style(UnderlineStyle::Curly)
+            }
+        );
+        let kind_only = config("7", r#"{"underline_style": "curly"}"#).style;
+        assert_eq!(kind_only, Style::new());
+        assert_eq!(kind_only.paint("x").to_string(), "x");
+        assert!(serde_json::from_str::<Config>(r#"{"color":256,"style":""}"#).is_err());
+        assert!(
+            serde_json::from_str::<Config>(r#"{"color":"red","style":{"shiny":true}}"#).is_err()
+        );
+        for style in [
+            r#"{"bold": true, "bold": false}"#,
+            r#"{"bold": true, "is_bold": true}"#,
+            r#"{"fg": "red", "foreground": "blue"}"#,
+            r#"{"is_fg": "red"}"#,
+            r#"{"is_prefix_with_reset": true}"#,
+        ] {
+            let json = format!(r#"{{"color":"red","style":{}}}"#, style);
+            assert!(serde_json::from_str::<Config>(&json).is_err(), "{}", style);
+        }
+    }
+
+    #[test]
+    fn old_format() {
+        for color in [Red, LightGray, Default, Fixed(100), Rgb(1, 2, 3)] {
+            for style in [
+                Style::new(),
+                Red.on(Fixed(3))
+                    .bold()
+                    .underline_style(UnderlineStyle::Double),
+                Rgb(1, 2, 3)
+                    .reset_before_style()
+                    .underline_color(Blue)
+                    .framed(),
+            ] {
+                let old = format!(
+                    r#"{{"color":{},"style":{}}}"#,
+                    serde_json::to_string(&color).unwrap(),
+                    serde_json::to_string(&style).unwrap()
+                );
+                let config: Config = serde_json::from_str(&old).unwrap();
+                assert_eq!(config, Config { color, style }, "{}", old);
+            }
+        }
+    }
+}
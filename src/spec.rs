@@ -0,0 +1,405 @@
+use crate::attributes::Attributes;
+use crate::color_spec::ColorSpec;
+use crate::style::{Color, Style, UnderlineStyle};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error from parsing a `Style` out of a string, pointing at the token
+/// that could not be understood.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::Style;
+///
+/// let error = "bold shiny red".parse::<Style>().unwrap_err();
+/// assert_eq!(error.token(), "shiny");
+/// assert_eq!(error.position(), 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseStyleError {
+    kind: ErrorKind,
+    token: String,
+    position: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+    UnknownToken,
+    InvalidColor,
+    MissingColor,
+    TooManyColors,
+}
+
+impl ParseStyleError {
+    fn new(kind: ErrorKind, token: &str, position: usize) -> ParseStyleError {
+        ParseStyleError {
+            kind,
+            token: token.to_owned(),
+            position,
+        }
+    }
+
+    /// The token that could not be parsed.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// The byte offset of the token in the parsed string.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for ParseStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::UnknownToken => write!(f, "unrecognized token `{}`", self.token)?,
+            ErrorKind::InvalidColor => write!(f, "invalid color `{}`", self.token)?,
+            ErrorKind::MissingColor => write!(f, "expected a color after `{}`", self.token)?,
+            ErrorKind::TooManyColors => write!(f, "unexpected third color `{}`", self.token)?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl Error for ParseStyleError {}
+
+/// Parses a style from a list of words separated by whitespace, such as
+/// `"bold italic red on #202020"` or `"underline fg=208 bg=black"`.
+///
+/// - attributes are given by name: `bold`, `dimmed` (or `dim`), `italic`,
+///   `underline` (or `ul`), `blink`, `rapid_blink`, `reverse`, `hidden`,
+///   `strikethrough` (or `strike`), `framed`, `encircled`, `overline`,
+///   `superscript` and `subscript`;
+/// - `underline=curly` picks the kind of underline, which is one of
+///   `single`, `double`, `curly`, `dotted` or `dashed`;
+/// - `fg=`, `bg=` and `underline_color=` set a color, as does `on` followed
+///   by a background color, or by `normal` for no background;
+/// - as in `git config`, the first bare color is the foreground and the
+///   second the background, and `normal` stands in for a color that is not
+///   set, so `"normal blue"` is only a background;
+/// - `reset` sets `prefix_with_reset`.
+///
+/// Colors are written in any of the forms accepted by `Color`'s `FromStr`
+/// implementation, such as `red`, `208` or `#202020`, without spaces. Words
+/// are not case-sensitive.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{Color, Style};
+///
+/// assert_eq!("bold ul red blue".parse(), Ok(Color::Red.on(Color::Blue).bold().underline()));
+/// assert_eq!(
+///     "underline fg=208 bg=black".parse(),
+///     Ok(Color::Fixed(208).on(Color::Black).underline())
+/// );
+/// ```
+impl FromStr for Style {
+    type Err = ParseStyleError;
+
+    fn from_str(s: &str) -> Result<Style, ParseStyleError> {
+        let mut style = Style::new();
+        let mut bare_colors = 0;
+        let mut words = words(s);
+
+        while let Some((word, position)) = words.next() {
+            let lower = word.to_ascii_lowercase();
+
+            if let Some((key, _)) = lower.split_once('=') {
+                let value = &word[key.len() + 1..];
+                let color = || {
+                    value.parse::<Color>().map_err(|_| {
+                        ParseStyleError::new(
+                            ErrorKind::InvalidColor,
+                            value,
+                            position + key.len() + 1,
+                        )
+                    })
+                };
+                match key {
+                    "fg" => style.foreground = Some(color()?),
+                    "bg" => style.background = Some(color()?),
+                    "underline_color" => style.underline_color = Some(color()?),
+                    "underline" | "ul" => {
+                        style.attributes.insert(Attributes::UNDERLINE);
+                        style.underline_style = parse_underline_style(value).ok_or_else(|| {
+                            ParseStyleError::new(
+                                ErrorKind::UnknownToken,
+                                value,
+                                position + key.len() + 1,
+                            )
+                        })?;
+                    }
+                    _ => {
+                        return Err(ParseStyleError::new(
+                            ErrorKind::UnknownToken,
+                            word,
+                            position,
+                        ))
+                    }
+                }
+            } else if lower == "on" {
+                let (value, position) = words
+                    .next()
+                    .ok_or_else(|| ParseStyleError::new(ErrorKind::MissingColor, word, position))?;
+                style.background = if value.eq_ignore_ascii_case("normal") {
+                    None
+                } else {
+                    let color = value.parse::<Color>().map_err(|_| {
+                        ParseStyleError::new(ErrorKind::InvalidColor, value, position)
+                    })?;
+                    Some(color)
+                };
+            } else if lower == "reset" {
+                style.prefix_with_reset = true;
+            } else if let Some(attribute) = parse_attribute(&lower) {
+                style.attributes.insert(attribute);
+            } else if lower == "normal" || word.parse::<Color>().is_ok() {
+                let color = word.parse().ok();
+                match bare_colors {
+                    0 => style.foreground = color,
+                    1 => style.background = color,
+                    _ => {
+                        return Err(ParseStyleError::new(
+                            ErrorKind::TooManyColors,
+                            word,
+                            position,
+                        ))
+                    }
+                }
+                bare_colors += 1;
+            } else {
+                return Err(ParseStyleError::new(
+                    ErrorKind::UnknownToken,
+                    word,
+                    position,
+                ));
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+/// Splits a string into the words between whitespace, each with the byte
+/// offset it starts at.
+fn words(s: &str) -> impl Iterator<Item = (&str, usize)> + '_ {
+    let mut start = None;
+    s.char_indices()
+        .chain(std::iter::once((s.len(), ' ')))
+        .filter_map(move |(i, c)| match (start, c.is_whitespace()) {
+            (None, false) => {
+                start = Some(i);
+                None
+            }
+            (Some(begin), true) => {
+                start = None;
+                Some((&s[begin..i], begin))
+            }
+            _ => None,
+        })
+}
+
+/// Writes a style in the syntax read by its `FromStr` implementation, so
+/// that it can be parsed back again. A plain style is written as `normal`.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{Color, Style, UnderlineStyle};
+///
+/// let style = Color::Red.on(Color::Rgb(32, 32, 32)).bold().underline_style(UnderlineStyle::Curly);
+/// assert_eq!(style.to_string(), "bold underline=curly red on #202020");
+/// assert_eq!(style.to_string().parse(), Ok(style));
+/// ```
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut words = Vec::new();
+        if self.prefix_with_reset {
+            words.push("reset".to_owned());
+        }
+        for (name, attribute) in Attributes::NAMED {
+            if attribute == Attributes::UNDERLINE || !self.attributes.contains(attribute) {
+                continue;
+            }
+            words.push(name.to_owned());
+        }
+        if self.is_underline() {
+            match self.underline_style {
+                UnderlineStyle::Single => words.push("underline".to_owned()),
+                kind => words.push(format!("underline={}", underline_style_name(kind))),
+            }
+        }
+        if let Some(fg) = self.foreground {
+            words.push(ColorSpec(fg).to_string());
+        }
+        if let Some(bg) = self.background {
+            words.push(format!("on {}", ColorSpec(bg)));
+        }
+        if let Some(color) = self.underline_color {
+            words.push(format!("underline_color={}", ColorSpec(color)));
+        }
+
+        if words.is_empty() {
+            f.write_str("normal")
+        } else {
+            f.write_str(&words.join(" "))
+        }
+    }
+}
+
+fn parse_attribute(s: &str) -> Option<Attributes> {
+    match s {
+        "dim" => Some(Attributes::DIMMED),
+        "ul" => Some(Attributes::UNDERLINE),
+        "strike" => Some(Attributes::STRIKETHROUGH),
+        _ => Attributes::NAMED
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, attribute)| *attribute),
+    }
+}
+
+pub(crate) fn parse_underline_style(s: &str) -> Option<UnderlineStyle> {
+    [
+        UnderlineStyle::Single,
+        UnderlineStyle::Double,
+        UnderlineStyle::Curly,
+        UnderlineStyle::Dotted,
+        UnderlineStyle::Dashed,
+    ]
+    .into_iter()
+    .find(|kind| s.eq_ignore_ascii_case(underline_style_name(*kind)))
+}
+
+pub(crate) fn underline_style_name(kind: UnderlineStyle) -> &'static str {
+    match kind {
+        UnderlineStyle::Single => "single",
+        UnderlineStyle::Double => "double",
+        UnderlineStyle::Curly => "curly",
+        UnderlineStyle::Dotted => "dotted",
+        UnderlineStyle::Dashed => "dashed",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ParseStyleError;
+    use crate::style::Color::*;
+    use crate::style::{Style, UnderlineStyle};
+
+    fn parse(s: &str) -> Result<Style, ParseStyleError> {
+        s.parse()
+    }
+
+    #[test]
+    fn attributes_and_colors() {
+        assert_eq!(
+            parse("bold italic red on #202020"),
+            Ok(Red.on(Rgb(32, 32, 32)).bold().italic())
+        );
+        assert_eq!(
+            parse("underline fg=208 bg=black"),
+            Ok(Fixed(208).on(Black).underline())
+        );
+        assert_eq!(
+            parse("  DIM   Strike  "),
+            Ok(Style::new().dimmed().strikethrough())
+        );
+        assert_eq!(parse("fg=#FA0"), Ok(Rgb(0xff, 0xaa, 0x00).normal()));
+        assert_eq!(
+            parse("reset light_blue"),
+            Ok(Style::new().fg(LightBlue).reset_before_style())
+        );
+        assert_eq!(parse(""), Ok(Style::new()));
+    }
+
+    #[test]
+    fn git_style() {
+        assert_eq!(
+            parse("bold ul red blue"),
+            Ok(Red.on(Blue).bold().underline())
+        );
+        assert_eq!(parse("normal blue"), Ok(Style::new().on(Blue)));
+        assert_eq!(parse("default"), Ok(Default.normal()));
+        assert_eq!(parse("bold red on normal"), Ok(Red.bold()));
+        assert_eq!(parse("normal ON Normal"), Ok(Style::new()));
+    }
+
+    #[test]
+    fn underlines() {
+        assert_eq!(
+            parse("underline=curly underline_color=9"),
+            Ok(Style::new()
+                .underline_style(UnderlineStyle::Curly)
+                .underline_color(Fixed(9)))
+        );
+        assert_eq!(parse("ul=Double"), Ok(Style::new().double_underline()));
+    }
+
+    #[test]
+    fn errors() {
+        let error = parse("bold shiny red").unwrap_err();
+        assert_eq!((error.token(), error.position()), ("shiny", 5));
+        assert_eq!(
+            error.to_string(),
+            "unrecognized token `shiny` at position 5"
+        );
+
+        let error = parse("fg=#12345").unwrap_err();
+        assert_eq!((error.token(), error.position()), ("#12345", 3));
+        assert_eq!(error.to_string(), "invalid color `#12345` at position 3");
+
+        let error = parse("red 256").unwrap_err();
+        assert_eq!((error.token(), error.position()), ("256", 4));
+
+        let error = parse("bold on").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected a color after `on` at position 5"
+        );
+
+        let error = parse("red blue green").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unexpected third color `green` at position 9"
+        );
+
+        let error = parse("\u{3000}bold\tred\u{a0}\u{a0}on  blau").unwrap_err();
+        assert_eq!((error.token(), error.position()), ("blau", 19));
+
+        let error = parse("ul=wavy").unwrap_err();
+        assert_eq!((error.token(), error.position()), ("wavy", 3));
+    }
+
+    #[test]
+    fn round_trip() {
+        let styles = [
+            Style::new(),
+            Red.bold(),
+            Style::new().on(Fixed(200)).italic().blink(),
+            Rgb(1, 2, 3)
+                .on(Default)
+                .underline_style(UnderlineStyle::Dotted)
+                .underline_color(LightMagenta),
+            Purple.reverse().hidden().overline().reset_before_style(),
+            Style::new()
+                .framed()
+                .encircled()
+                .superscript()
+                .subscript()
+                .rapid_blink(),
+        ];
+        for style in styles {
+            assert_eq!(parse(&style.to_string()), Ok(style), "{}", style);
+        }
+        assert_eq!(Style::new().to_string(), "normal");
+        assert_eq!(
+            Cyan.on(LightGray).underline().to_string(),
+            "underline cyan on light_gray"
+        );
+    }
+}
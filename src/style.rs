@@ -1,3 +1,5 @@
+use crate::attributes::Attributes;
+
 /// A style is a collection of properties that can format a string
 /// using ANSI escape codes.
 ///
@@ -9,10 +11,11 @@
 /// let style = Style::new().bold().on(Color::Black);
 /// println!("{}", style.paint("Bold on black"));
 /// ```
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Eq, Clone, Copy)]
 #[cfg_attr(
     feature = "derive_serde_style",
-    derive(serde::Deserialize, serde::Serialize)
+    derive(serde::Deserialize, serde::Serialize),
+    serde(from = "StyleRepr", into = "StyleRepr")
 )]
 pub struct Style {
     /// The style's foreground color, if it has one.
@@ -21,34 +24,38 @@ pub struct Style {
     /// The style's background color, if it has one.
     pub background: Option<Color>,
 
-    /// Whether this style is bold.
-    pub is_bold: bool,
-
-    /// Whether this style is dimmed.
-    pub is_dimmed: bool,
-
-    /// Whether this style is italic.
-    pub is_italic: bool,
-
-    /// Whether this style is underlined.
-    pub is_underline: bool,
-
-    /// Whether this style is blinking.
-    pub is_blink: bool,
-
-    /// Whether this style has reverse colors.
-    pub is_reverse: bool,
+    /// The style's underline color, if it has one. Without one, underlines
+    /// are drawn in the foreground color.
+    pub underline_color: Option<Color>,
 
-    /// Whether this style is hidden.
-    pub is_hidden: bool,
+    /// The style's text attributes, such as bold or underlined.
+    ///
+    /// These used to be separate fields such as `is_bold`; they can be read
+    /// with methods of the same name, such as [`Style::is_bold`].
+    pub attributes: Attributes,
 
-    /// Whether this style is struckthrough.
-    pub is_strikethrough: bool,
+    /// The kind of line to underline with, if this style is underlined.
+    /// Without `Attributes::UNDERLINE`, it has no effect, and isn’t taken
+    /// into account when comparing styles.
+    pub underline_style: UnderlineStyle,
 
     /// Wether this style is always displayed starting with a reset code to clear any remaining style artifacts
     pub prefix_with_reset: bool,
 }
 
+/// Styles are equal when they look the same, so the underline kind is only
+/// compared between styles that are underlined.
+impl PartialEq for Style {
+    fn eq(&self, other: &Style) -> bool {
+        self.foreground == other.foreground
+            && self.background == other.background
+            && self.underline_color == other.underline_color
+            && self.attributes == other.attributes
+            && (!self.is_underline() || self.underline_style == other.underline_style)
+            && self.prefix_with_reset == other.prefix_with_reset
+    }
+}
+
 impl Style {
     /// Creates a new Style with no properties set.
     ///
@@ -93,7 +100,7 @@ impl Style {
     /// ```
     pub const fn bold(&self) -> Style {
         Style {
-            is_bold: true,
+            attributes: self.attributes.union(Attributes::BOLD),
             ..*self
         }
     }
@@ -110,7 +117,7 @@ impl Style {
     /// ```
     pub const fn dimmed(&self) -> Style {
         Style {
-            is_dimmed: true,
+            attributes: self.attributes.union(Attributes::DIMMED),
             ..*self
         }
     }
@@ -127,7 +134,7 @@ impl Style {
     /// ```
     pub const fn italic(&self) -> Style {
         Style {
-            is_italic: true,
+            attributes: self.attributes.union(Attributes::ITALIC),
             ..*self
         }
     }
@@ -144,7 +151,30 @@ impl Style {
     /// ```
     pub const fn underline(&self) -> Style {
         Style {
-            is_underline: true,
+            attributes: self.attributes.union(Attributes::UNDERLINE),
+            ..*self
+        }
+    }
+
+    /// Returns a `Style` with the underline property set, using the given
+    /// kind of line.
+    ///
+    /// Terminals that don’t support other kinds of underlines show a single
+    /// line instead, or nothing at all. See
+    /// [`Capabilities::styled_underlines`](crate::Capabilities::styled_underlines).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Style, UnderlineStyle};
+    ///
+    /// let style = Style::new().underline_style(UnderlineStyle::Curly);
+    /// println!("{}", style.paint("typo"));
+    /// ```
+    pub const fn underline_style(&self, underline_style: UnderlineStyle) -> Style {
+        Style {
+            attributes: self.attributes.union(Attributes::UNDERLINE),
+            underline_style,
             ..*self
         }
     }
@@ -160,7 +190,7 @@ impl Style {
     /// ```
     pub const fn blink(&self) -> Style {
         Style {
-            is_blink: true,
+            attributes: self.attributes.union(Attributes::BLINK),
             ..*self
         }
     }
@@ -177,7 +207,7 @@ impl Style {
     /// ```
     pub const fn reverse(&self) -> Style {
         Style {
-            is_reverse: true,
+            attributes: self.attributes.union(Attributes::REVERSE),
             ..*self
         }
     }
@@ -194,7 +224,7 @@ impl Style {
     /// ```
     pub const fn hidden(&self) -> Style {
         Style {
-            is_hidden: true,
+            attributes: self.attributes.union(Attributes::HIDDEN),
             ..*self
         }
     }
@@ -211,11 +241,129 @@ impl Style {
     /// ```
     pub const fn strikethrough(&self) -> Style {
         Style {
-            is_strikethrough: true,
+            attributes: self.attributes.union(Attributes::STRIKETHROUGH),
+            ..*self
+        }
+    }
+
+    /// Returns a `Style` with the overlined property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Style;
+    ///
+    /// let style = Style::new().overline();
+    /// println!("{}", style.paint("over the top"));
+    /// ```
+    pub const fn overline(&self) -> Style {
+        Style {
+            attributes: self.attributes.union(Attributes::OVERLINE),
+            ..*self
+        }
+    }
+
+    /// Returns a `Style` with the rapid blink property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Style;
+    ///
+    /// let style = Style::new().rapid_blink();
+    /// println!("{}", style.paint("hurry"));
+    /// ```
+    pub const fn rapid_blink(&self) -> Style {
+        Style {
+            attributes: self.attributes.union(Attributes::RAPID_BLINK),
+            ..*self
+        }
+    }
+
+    /// Returns a `Style` with the framed property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Style;
+    ///
+    /// let style = Style::new().framed();
+    /// println!("{}", style.paint("in a box"));
+    /// ```
+    pub const fn framed(&self) -> Style {
+        Style {
+            attributes: self.attributes.union(Attributes::FRAMED),
+            ..*self
+        }
+    }
+
+    /// Returns a `Style` with the encircled property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Style;
+    ///
+    /// let style = Style::new().encircled();
+    /// println!("{}", style.paint("in a circle"));
+    /// ```
+    pub const fn encircled(&self) -> Style {
+        Style {
+            attributes: self.attributes.union(Attributes::ENCIRCLED),
             ..*self
         }
     }
 
+    /// Returns a `Style` with the superscript property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Style;
+    ///
+    /// let style = Style::new().superscript();
+    /// println!("{}", style.paint("2"));
+    /// ```
+    pub const fn superscript(&self) -> Style {
+        Style {
+            attributes: self.attributes.union(Attributes::SUPERSCRIPT),
+            ..*self
+        }
+    }
+
+    /// Returns a `Style` with the subscript property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Style;
+    ///
+    /// let style = Style::new().subscript();
+    /// println!("{}", style.paint("2"));
+    /// ```
+    pub const fn subscript(&self) -> Style {
+        Style {
+            attributes: self.attributes.union(Attributes::SUBSCRIPT),
+            ..*self
+        }
+    }
+
+    /// Returns a `Style` with the underline property set, using two lines.
+    /// This is the same as `underline_style(UnderlineStyle::Double)`, and
+    /// is written as `4:2` rather than `21`; see [`UnderlineStyle::Double`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Style;
+    ///
+    /// let style = Style::new().double_underline();
+    /// println!("{}", style.paint("important"));
+    /// ```
+    pub const fn double_underline(&self) -> Style {
+        self.underline_style(UnderlineStyle::Double)
+    }
+
     /// Returns a `Style` with the foreground color property set.
     ///
     /// # Examples
@@ -250,6 +398,31 @@ impl Style {
         }
     }
 
+    /// Returns a `Style` with the underline color property set. This only
+    /// changes the color of the underline, so it’s usually combined with
+    /// [`Style::underline`] or [`Style::underline_style`].
+    ///
+    /// Terminals that don’t support underline colors draw the underline in
+    /// the foreground color instead. See
+    /// [`Capabilities::styled_underlines`](crate::Capabilities::styled_underlines).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Style, Color, UnderlineStyle};
+    ///
+    /// let style = Style::new()
+    ///     .underline_style(UnderlineStyle::Curly)
+    ///     .underline_color(Color::Red);
+    /// println!("{}", style.paint("misspelt"));
+    /// ```
+    pub const fn underline_color(&self, underline_color: Color) -> Style {
+        Style {
+            underline_color: Some(underline_color),
+            ..*self
+        }
+    }
+
     /// Return true if this `Style` has no actual styles, and can be written
     /// without any control characters.
     ///
@@ -264,6 +437,76 @@ impl Style {
     pub fn is_plain(self) -> bool {
         self == Style::default()
     }
+
+    /// Whether this style is bold.
+    pub const fn is_bold(&self) -> bool {
+        self.attributes.contains(Attributes::BOLD)
+    }
+
+    /// Whether this style is dimmed.
+    pub const fn is_dimmed(&self) -> bool {
+        self.attributes.contains(Attributes::DIMMED)
+    }
+
+    /// Whether this style is italic.
+    pub const fn is_italic(&self) -> bool {
+        self.attributes.contains(Attributes::ITALIC)
+    }
+
+    /// Whether this style is underlined.
+    pub const fn is_underline(&self) -> bool {
+        self.attributes.contains(Attributes::UNDERLINE)
+    }
+
+    /// Whether this style is blinking.
+    pub const fn is_blink(&self) -> bool {
+        self.attributes.contains(Attributes::BLINK)
+    }
+
+    /// Whether this style has reverse colors.
+    pub const fn is_reverse(&self) -> bool {
+        self.attributes.contains(Attributes::REVERSE)
+    }
+
+    /// Whether this style is hidden.
+    pub const fn is_hidden(&self) -> bool {
+        self.attributes.contains(Attributes::HIDDEN)
+    }
+
+    /// Whether this style is struckthrough.
+    pub const fn is_strikethrough(&self) -> bool {
+        self.attributes.contains(Attributes::STRIKETHROUGH)
+    }
+
+    /// Whether this style is overlined.
+    pub const fn is_overline(&self) -> bool {
+        self.attributes.contains(Attributes::OVERLINE)
+    }
+
+    /// Whether this style is blinking rapidly.
+    pub const fn is_rapid_blink(&self) -> bool {
+        self.attributes.contains(Attributes::RAPID_BLINK)
+    }
+
+    /// Whether this style is framed.
+    pub const fn is_framed(&self) -> bool {
+        self.attributes.contains(Attributes::FRAMED)
+    }
+
+    /// Whether this style is encircled.
+    pub const fn is_encircled(&self) -> bool {
+        self.attributes.contains(Attributes::ENCIRCLED)
+    }
+
+    /// Whether this style is superscript.
+    pub const fn is_superscript(&self) -> bool {
+        self.attributes.contains(Attributes::SUPERSCRIPT)
+    }
+
+    /// Whether this style is subscript.
+    pub const fn is_subscript(&self) -> bool {
+        self.attributes.contains(Attributes::SUBSCRIPT)
+    }
 }
 
 impl Default for Style {
@@ -274,26 +517,160 @@ impl Default for Style {
     /// use nu_ansi_term::Style;
     /// assert_eq!(None,  Style::default().foreground);
     /// assert_eq!(None,  Style::default().background);
-    /// assert_eq!(false, Style::default().is_bold);
+    /// assert_eq!(false, Style::default().is_bold());
     /// assert_eq!("txt", Style::default().paint("txt").to_string());
     /// ```
     fn default() -> Style {
         Style {
             foreground: None,
             background: None,
-            is_bold: false,
-            is_dimmed: false,
-            is_italic: false,
-            is_underline: false,
-            is_blink: false,
-            is_reverse: false,
-            is_hidden: false,
-            is_strikethrough: false,
+            underline_color: None,
+            attributes: Attributes::empty(),
+            underline_style: UnderlineStyle::Single,
             prefix_with_reset: false,
         }
     }
 }
 
+/// The kind of line that underlined text is drawn with.
+///
+/// Apart from `Single`, these are written using the
+/// subparameters of the underline code, as in `4:3` for a curly underline,
+/// which are supported by terminals such as kitty, WezTerm, foot, and those
+/// based on VTE. [`Style::sgr_params`] leaves the subparameter out and
+/// writes a plain `4`, since `LS_COLORS` uses colons to separate entries.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default, Hash)]
+#[cfg_attr(
+    feature = "derive_serde_style",
+    derive(serde::Deserialize, serde::Serialize)
+)]
+pub enum UnderlineStyle {
+    /// A single straight line (code `4`).
+    #[default]
+    Single,
+
+    /// Two straight lines (code `4:2`).
+    ///
+    /// The older code `21` is never written for this, even for terminals
+    /// without subparameters, because the Linux console and many xterm
+    /// builds read it as “bold off” instead. Those get a single underline.
+    /// When reading escape codes, `21` is still taken to mean this.
+    Double,
+
+    /// A wavy line, as often used for spelling mistakes (code `4:3`).
+    Curly,
+
+    /// A dotted line (code `4:4`).
+    Dotted,
+
+    /// A dashed line (code `4:5`).
+    Dashed,
+}
+
+/// The way a `Style` is serialized: the same as before its attributes were
+/// stored as a bitset, with a field for each one. The fields that were added
+/// later are left out when they aren't set, and can be missing.
+#[cfg(feature = "derive_serde_style")]
+#[derive(serde::Deserialize, serde::Serialize)]
+struct StyleRepr {
+    foreground: Option<Color>,
+    background: Option<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    underline_color: Option<Color>,
+    is_bold: bool,
+    is_dimmed: bool,
+    is_italic: bool,
+    is_underline: bool,
+    #[serde(default, skip_serializing_if = "UnderlineStyle::is_single")]
+    underline_style: UnderlineStyle,
+    is_blink: bool,
+    is_reverse: bool,
+    is_hidden: bool,
+    is_strikethrough: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    is_overline: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    is_rapid_blink: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    is_framed: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    is_encircled: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    is_superscript: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    is_subscript: bool,
+    prefix_with_reset: bool,
+}
+
+#[cfg(feature = "derive_serde_style")]
+impl From<StyleRepr> for Style {
+    fn from(repr: StyleRepr) -> Style {
+        let mut attributes = Attributes::empty();
+        attributes.set(Attributes::BOLD, repr.is_bold);
+        attributes.set(Attributes::DIMMED, repr.is_dimmed);
+        attributes.set(Attributes::ITALIC, repr.is_italic);
+        attributes.set(Attributes::UNDERLINE, repr.is_underline);
+        attributes.set(Attributes::BLINK, repr.is_blink);
+        attributes.set(Attributes::REVERSE, repr.is_reverse);
+        attributes.set(Attributes::HIDDEN, repr.is_hidden);
+        attributes.set(Attributes::STRIKETHROUGH, repr.is_strikethrough);
+        attributes.set(Attributes::OVERLINE, repr.is_overline);
+        attributes.set(Attributes::RAPID_BLINK, repr.is_rapid_blink);
+        attributes.set(Attributes::FRAMED, repr.is_framed);
+        attributes.set(Attributes::ENCIRCLED, repr.is_encircled);
+        attributes.set(Attributes::SUPERSCRIPT, repr.is_superscript);
+        attributes.set(Attributes::SUBSCRIPT, repr.is_subscript);
+
+        Style {
+            foreground: repr.foreground,
+            background: repr.background,
+            underline_color: repr.underline_color,
+            attributes,
+            underline_style: repr.underline_style,
+            prefix_with_reset: repr.prefix_with_reset,
+        }
+    }
+}
+
+#[cfg(feature = "derive_serde_style")]
+impl From<Style> for StyleRepr {
+    fn from(style: Style) -> StyleRepr {
+        StyleRepr {
+            foreground: style.foreground,
+            background: style.background,
+            underline_color: style.underline_color,
+            is_bold: style.is_bold(),
+            is_dimmed: style.is_dimmed(),
+            is_italic: style.is_italic(),
+            is_underline: style.is_underline(),
+            underline_style: style.underline_style,
+            is_blink: style.is_blink(),
+            is_reverse: style.is_reverse(),
+            is_hidden: style.is_hidden(),
+            is_strikethrough: style.is_strikethrough(),
+            is_overline: style.is_overline(),
+            is_rapid_blink: style.is_rapid_blink(),
+            is_framed: style.is_framed(),
+            is_encircled: style.is_encircled(),
+            is_superscript: style.is_superscript(),
+            is_subscript: style.is_subscript(),
+            prefix_with_reset: style.prefix_with_reset,
+        }
+    }
+}
+
+#[cfg(feature = "derive_serde_style")]
+impl UnderlineStyle {
+    fn is_single(&self) -> bool {
+        *self == UnderlineStyle::Single
+    }
+}
+
+#[cfg(feature = "derive_serde_style")]
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 // ---- colors ----
 
 /// A color is one specific type of ANSI escape code, and can refer
@@ -425,7 +802,7 @@ impl Color {
     pub fn bold(self) -> Style {
         Style {
             foreground: Some(self),
-            is_bold: true,
+            attributes: Attributes::BOLD,
             ..Style::default()
         }
     }
@@ -444,7 +821,7 @@ impl Color {
     pub fn dimmed(self) -> Style {
         Style {
             foreground: Some(self),
-            is_dimmed: true,
+            attributes: Attributes::DIMMED,
             ..Style::default()
         }
     }
@@ -463,7 +840,7 @@ impl Color {
     pub fn italic(self) -> Style {
         Style {
             foreground: Some(self),
-            is_italic: true,
+            attributes: Attributes::ITALIC,
             ..Style::default()
         }
     }
@@ -482,7 +859,27 @@ impl Color {
     pub fn underline(self) -> Style {
         Style {
             foreground: Some(self),
-            is_underline: true,
+            attributes: Attributes::UNDERLINE,
+            ..Style::default()
+        }
+    }
+
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// underline property set, using the given kind of line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, UnderlineStyle};
+    ///
+    /// let style = Color::Red.underline_style(UnderlineStyle::Curly);
+    /// println!("{}", style.paint("error"));
+    /// ```
+    pub fn underline_style(self, underline_style: UnderlineStyle) -> Style {
+        Style {
+            foreground: Some(self),
+            attributes: Attributes::UNDERLINE,
+            underline_style,
             ..Style::default()
         }
     }
@@ -501,7 +898,7 @@ impl Color {
     pub fn blink(self) -> Style {
         Style {
             foreground: Some(self),
-            is_blink: true,
+            attributes: Attributes::BLINK,
             ..Style::default()
         }
     }
@@ -520,7 +917,7 @@ impl Color {
     pub fn reverse(self) -> Style {
         Style {
             foreground: Some(self),
-            is_reverse: true,
+            attributes: Attributes::REVERSE,
             ..Style::default()
         }
     }
@@ -539,7 +936,7 @@ impl Color {
     pub fn hidden(self) -> Style {
         Style {
             foreground: Some(self),
-            is_hidden: true,
+            attributes: Attributes::HIDDEN,
             ..Style::default()
         }
     }
@@ -558,11 +955,141 @@ impl Color {
     pub fn strikethrough(self) -> Style {
         Style {
             foreground: Some(self),
-            is_strikethrough: true,
+            attributes: Attributes::STRIKETHROUGH,
+            ..Style::default()
+        }
+    }
+
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// overlined property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// let style = Color::Cyan.overline();
+    /// println!("{}", style.paint("over the top"));
+    /// ```
+    pub fn overline(self) -> Style {
+        Style {
+            foreground: Some(self),
+            attributes: Attributes::OVERLINE,
+            ..Style::default()
+        }
+    }
+
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// rapid blink property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// let style = Color::Cyan.rapid_blink();
+    /// println!("{}", style.paint("hurry"));
+    /// ```
+    pub fn rapid_blink(self) -> Style {
+        Style {
+            foreground: Some(self),
+            attributes: Attributes::RAPID_BLINK,
+            ..Style::default()
+        }
+    }
+
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// framed property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// let style = Color::Cyan.framed();
+    /// println!("{}", style.paint("in a box"));
+    /// ```
+    pub fn framed(self) -> Style {
+        Style {
+            foreground: Some(self),
+            attributes: Attributes::FRAMED,
+            ..Style::default()
+        }
+    }
+
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// encircled property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// let style = Color::Cyan.encircled();
+    /// println!("{}", style.paint("in a circle"));
+    /// ```
+    pub fn encircled(self) -> Style {
+        Style {
+            foreground: Some(self),
+            attributes: Attributes::ENCIRCLED,
             ..Style::default()
         }
     }
 
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// superscript property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// let style = Color::Cyan.superscript();
+    /// println!("{}", style.paint("2"));
+    /// ```
+    pub fn superscript(self) -> Style {
+        Style {
+            foreground: Some(self),
+            attributes: Attributes::SUPERSCRIPT,
+            ..Style::default()
+        }
+    }
+
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// subscript property set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// let style = Color::Cyan.subscript();
+    /// println!("{}", style.paint("2"));
+    /// ```
+    pub fn subscript(self) -> Style {
+        Style {
+            foreground: Some(self),
+            attributes: Attributes::SUBSCRIPT,
+            ..Style::default()
+        }
+    }
+
+    /// Returns a `Style` with the foreground color set to this color and the
+    /// underline property set, using two lines, written as `4:2` rather than
+    /// `21`; see [`UnderlineStyle::Double`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color;
+    ///
+    /// let style = Color::Red.double_underline();
+    /// println!("{}", style.paint("important"));
+    /// ```
+    pub fn double_underline(self) -> Style {
+        self.underline_style(UnderlineStyle::Double)
+    }
+
     /// Returns a `Style` thats resets all styling before applying
     /// the foreground color set to this color.
     ///
@@ -621,7 +1148,7 @@ impl From<Color> for Style {
 #[cfg(test)]
 #[cfg(feature = "derive_serde_style")]
 mod serde_json_tests {
-    use super::{Color, Style};
+    use super::{Color, Style, UnderlineStyle};
 
     #[test]
     fn color_serialization() {
@@ -661,4 +1188,44 @@ mod serde_json_tests {
 
         assert_eq!(serde_json::to_string(&style).unwrap(), "{\"foreground\":null,\"background\":null,\"is_bold\":false,\"is_dimmed\":false,\"is_italic\":false,\"is_underline\":false,\"is_blink\":false,\"is_reverse\":false,\"is_hidden\":false,\"is_strikethrough\":false,\"prefix_with_reset\":false}".to_string());
     }
+
+    #[test]
+    fn underline_style_serialization() {
+        let style = Style::new().underline_style(UnderlineStyle::Curly);
+        let serialized = serde_json::to_string(&style).unwrap();
+        assert!(serialized.contains("\"underline_style\":\"Curly\""));
+        assert_eq!(serde_json::from_str::<Style>(&serialized).unwrap(), style);
+
+        let old = "{\"foreground\":null,\"background\":null,\"is_bold\":false,\"is_dimmed\":false,\"is_italic\":false,\"is_underline\":true,\"is_blink\":false,\"is_reverse\":false,\"is_hidden\":false,\"is_strikethrough\":false,\"prefix_with_reset\":false}";
+        assert_eq!(
+            serde_json::from_str::<Style>(old).unwrap(),
+            Style::new().underline()
+        );
+
+        let stale = old.replace(
+            "\"is_underline\":true",
+            "\"is_underline\":false,\"underline_style\":\"Curly\"",
+        );
+        let stale = serde_json::from_str::<Style>(&stale).unwrap();
+        assert!(stale.is_plain());
+        assert_eq!(stale.paint("x").to_string(), "x");
+    }
+
+    #[test]
+    fn underline_color_serialization() {
+        let style = Style::new().underline().underline_color(Color::Fixed(9));
+        let serialized = serde_json::to_string(&style).unwrap();
+        assert!(serialized.contains("\"underline_color\":{\"Fixed\":9}"));
+        assert_eq!(serde_json::from_str::<Style>(&serialized).unwrap(), style);
+    }
+
+    #[test]
+    fn attributes_serialization() {
+        let style = Color::Red.bold().italic().rapid_blink().overline();
+        let serialized = serde_json::to_string(&style).unwrap();
+        assert!(serialized.contains("\"is_bold\":true,\"is_dimmed\":false,\"is_italic\":true"));
+        assert!(serialized.contains("\"is_overline\":true,\"is_rapid_blink\":true"));
+        assert!(!serialized.contains("is_framed"));
+        assert_eq!(serde_json::from_str::<Style>(&serialized).unwrap(), style);
+    }
 }
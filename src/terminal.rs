@@ -0,0 +1,372 @@
+use crate::render::ColorDepth;
+use std::env;
+use std::ffi::OsString;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// Whether colors are enabled, or `UNSET` before `NO_COLOR` has been read.
+static COLORS_ENABLED: AtomicU8 = AtomicU8::new(UNSET);
+const UNSET: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+/// Turns escape sequences on or off for everything written by this crate
+/// through `Display` or `write_to`, across the whole process.
+///
+/// While they’re off, `Prefix`, `Infix` and `Suffix` values write nothing,
+/// and `AnsiString`s and `AnsiStrings` write only their text, without any
+/// styles, hyperlinks or titles. This makes it possible to support something
+/// like `--color=never` without passing a flag to every place that paints
+/// text. Calling this overrides the default taken from `NO_COLOR`.
+///
+/// Values rendered with a `render` method aren’t affected by this: they use
+/// the capabilities they were given instead, which allows a single writer to
+/// override the global setting.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{set_colors_enabled, ColorDepth, Color::Red};
+///
+/// set_colors_enabled(false);
+/// assert_eq!(Red.paint("text").to_string(), "text");
+/// assert_eq!(Red.paint("text").render(ColorDepth::Ansi16).to_string(), "\x1b[31mtext\x1b[0m");
+///
+/// set_colors_enabled(true);
+/// assert_eq!(Red.paint("text").to_string(), "\x1b[31mtext\x1b[0m");
+/// ```
+pub fn set_colors_enabled(enabled: bool) {
+    let state = if enabled { ENABLED } else { DISABLED };
+    COLORS_ENABLED.store(state, Ordering::Relaxed);
+}
+
+/// Returns whether escape sequences are written by `Display` and `write_to`.
+///
+/// Until [`set_colors_enabled`] is called, they are, unless [`no_color`]
+/// says that `NO_COLOR` asks for output without colors. The variable is read
+/// the first time this is needed, and not again.
+pub fn colors_enabled() -> bool {
+    match COLORS_ENABLED.load(Ordering::Relaxed) {
+        ENABLED => true,
+        DISABLED => false,
+        _ => {
+            let enabled = !no_color();
+            let state = if enabled { ENABLED } else { DISABLED };
+            // If the setting was changed in the meantime, that one wins.
+            match COLORS_ENABLED.compare_exchange(
+                UNSET,
+                state,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => enabled,
+                Err(state) => state == ENABLED,
+            }
+        }
+    }
+}
+
+/// Returns whether the `NO_COLOR` environment variable asks for output
+/// without colors, which it does when it’s set to anything but an empty
+/// string.
+///
+/// This is what [`colors_enabled`] defaults to, and what
+/// [`Capabilities::detect`] looks at first.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{colors_enabled, no_color};
+///
+/// assert_eq!(colors_enabled(), !no_color());
+/// ```
+pub fn no_color() -> bool {
+    env::var_os("NO_COLOR").map_or(false, |v| !v.is_empty())
+}
+
+/// What a terminal, or whatever else output is being written to, can be
+/// expected to show.
+///
+/// This is usually worked out by [`Capabilities::detect`] from the
+/// environment the program runs in, and passed to the `render` methods of
+/// the other types in this crate so that they only write the escape
+/// sequences that will be understood.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// How many colors can be shown.
+    pub color_depth: ColorDepth,
+
+    /// Whether hyperlinks (OSC 8 sequences) can be written. When they can’t,
+    /// only the text of a link is.
+    pub hyperlinks: bool,
+
+    /// Whether window titles (OSC 2 sequences) can be written. When they
+    /// can’t, titles are left out completely.
+    pub titles: bool,
+
+    /// How to turn off styles between one piece of text and the next.
+    pub transitions: Transitions,
+
+    /// Whether underlines other than `UnderlineStyle::Single` can be written,
+    /// using the colon-separated subparameters of the underline code, along
+    /// with underline colors. When they can’t, a single underline is written
+    /// instead, in the foreground color.
+    pub styled_underlines: bool,
+}
+
+/// The ways of turning off styles that the next piece of text doesn’t have.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transitions {
+    /// Reset all styles, then turn on those of the next piece of text again.
+    /// This works on every terminal, and is what `Display` does.
+    Reset,
+
+    /// Use the codes that turn off only one style, such as `22` for bold and
+    /// dimmed text, `24` for underlines, or `39` for the foreground color.
+    /// The output is shorter, and doesn’t flicker on slow terminals, but
+    /// some very old terminals don’t understand these codes.
+    ///
+    /// Going from bold red text to plain red text writes `\x1B[22m` instead
+    /// of `\x1B[0m\x1B[31m`.
+    Minimal,
+}
+
+/// Capabilities for a color depth, with hyperlinks, titles and styled
+/// underlines allowed unless it’s `ColorDepth::NoColor`, and styles turned
+/// off with resets.
+impl From<ColorDepth> for Capabilities {
+    fn from(color_depth: ColorDepth) -> Capabilities {
+        let escapes = color_depth != ColorDepth::NoColor;
+        Capabilities {
+            color_depth,
+            hyperlinks: escapes,
+            titles: escapes,
+            transitions: Transitions::Reset,
+            styled_underlines: escapes,
+        }
+    }
+}
+
+impl Capabilities {
+    /// The capabilities used when writing without `render`, which depend on
+    /// [`colors_enabled`].
+    pub(crate) fn global() -> Capabilities {
+        if colors_enabled() {
+            ColorDepth::TrueColor.into()
+        } else {
+            ColorDepth::NoColor.into()
+        }
+    }
+
+    /// Works out what a stream can show from the environment variables of
+    /// the current process. `is_tty` says whether the stream is a terminal;
+    /// on Rust 1.70 or later, this can be found out with
+    /// `std::io::IsTerminal`.
+    ///
+    /// See [`Capabilities::detect_from`] for the rules that are followed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Capabilities, Color::Green};
+    ///
+    /// let caps = Capabilities::detect(false);
+    /// println!("{}", Green.paint("done").render(caps));
+    /// ```
+    pub fn detect(is_tty: bool) -> Capabilities {
+        Capabilities::detect_from(is_tty, |name| env::var_os(name))
+    }
+
+    /// Works out what a stream can show from environment variables looked up
+    /// with the given function, rather than those of the current process.
+    ///
+    /// The color depth is decided by the first of these that applies:
+    ///
+    /// 1. If `NO_COLOR` is set to anything but an empty string, there are
+    ///    no colors.
+    /// 2. If `CLICOLOR_FORCE` is set to anything but an empty string or `0`,
+    ///    there are colors, even when the stream isn’t a terminal.
+    /// 3. If the stream isn’t a terminal, `CLICOLOR` is `0`, or `TERM` is
+    ///    `dumb`, there are no colors.
+    /// 4. If `COLORTERM` is `truecolor` or `24bit`, or `TERM` ends in
+    ///    `-direct`, any 24-bit color can be shown.
+    /// 5. If `TERM` contains `256color`, the 256 colors of the xterm palette
+    ///    can be shown.
+    /// 6. Otherwise, only the sixteen basic colors can be shown.
+    ///
+    /// Hyperlinks and titles are only written to terminals whose `TERM`
+    /// isn’t `dumb`, since other programs reading the output would otherwise
+    /// see the escape sequences. They are written whether or not there are
+    /// colors, except that hyperlinks are left out when `NO_COLOR` asks for
+    /// plain output.
+    ///
+    /// Styles are turned off with `Transitions::Minimal`, since the codes it
+    /// uses are understood by every terminal that is still in common use.
+    ///
+    /// Styled underlines are only used on terminals known to support them:
+    /// those whose `TERM` mentions kitty, foot, WezTerm, Alacritty, Ghostty
+    /// or Contour, those based on VTE (which set `VTE_VERSION`), and those
+    /// whose `TERM_PROGRAM` is `WezTerm`, `iTerm.app` or `ghostty`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Capabilities, ColorDepth};
+    ///
+    /// let caps = Capabilities::detect_from(true, |name| match name {
+    ///     "TERM" => Some("xterm-256color".into()),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(caps.color_depth, ColorDepth::Ansi256);
+    /// assert!(caps.hyperlinks);
+    /// ```
+    pub fn detect_from<F>(is_tty: bool, mut env: F) -> Capabilities
+    where
+        F: FnMut(&str) -> Option<OsString>,
+    {
+        let no_color = env("NO_COLOR").map_or(false, |v| !v.is_empty());
+        let force = env("CLICOLOR_FORCE").map_or(false, |v| !v.is_empty() && v != "0");
+        let clicolor = env("CLICOLOR").map_or(true, |v| v != "0");
+        let colorterm = env("COLORTERM").unwrap_or_default();
+        let term = env("TERM").unwrap_or_default();
+        let term = term.to_string_lossy();
+        let dumb = term == "dumb";
+
+        let color_depth = if no_color || (!force && (!is_tty || !clicolor || dumb)) {
+            ColorDepth::NoColor
+        } else if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+
+        let term_program = env("TERM_PROGRAM").unwrap_or_default();
+        let styled_underlines = [
+            "kitty",
+            "foot",
+            "wezterm",
+            "alacritty",
+            "ghostty",
+            "contour",
+        ]
+        .iter()
+        .any(|name| term.contains(name))
+            || env("VTE_VERSION").map_or(false, |v| !v.is_empty())
+            || term_program == "WezTerm"
+            || term_program == "iTerm.app"
+            || term_program == "ghostty";
+
+        let escapes = is_tty && !dumb;
+        Capabilities {
+            color_depth,
+            hyperlinks: escapes && !no_color,
+            titles: escapes,
+            transitions: Transitions::Minimal,
+            styled_underlines: color_depth != ColorDepth::NoColor && styled_underlines,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Capabilities, Transitions};
+    use crate::render::ColorDepth::{self, *};
+
+    fn depth(is_tty: bool, vars: &[(&str, &str)]) -> ColorDepth {
+        detect(is_tty, vars).color_depth
+    }
+
+    fn detect(is_tty: bool, vars: &[(&str, &str)]) -> Capabilities {
+        Capabilities::detect_from(is_tty, |name| {
+            vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.into())
+        })
+    }
+
+    #[test]
+    fn terms() {
+        assert_eq!(depth(true, &[]), Ansi16);
+        assert_eq!(depth(true, &[("TERM", "xterm")]), Ansi16);
+        assert_eq!(depth(true, &[("TERM", "xterm-256color")]), Ansi256);
+        assert_eq!(depth(true, &[("TERM", "xterm-direct")]), TrueColor);
+        assert_eq!(depth(true, &[("TERM", "dumb")]), NoColor);
+    }
+
+    #[test]
+    fn colorterm() {
+        let vars = [("TERM", "xterm-256color"), ("COLORTERM", "truecolor")];
+        assert_eq!(depth(true, &vars), TrueColor);
+        assert_eq!(depth(true, &[("COLORTERM", "24bit")]), TrueColor);
+        assert_eq!(depth(true, &[("COLORTERM", "yes")]), Ansi16);
+    }
+
+    #[test]
+    fn not_a_tty() {
+        assert_eq!(depth(false, &[("TERM", "xterm-256color")]), NoColor);
+        assert!(!detect(false, &[]).hyperlinks);
+        assert!(!detect(false, &[]).titles);
+    }
+
+    #[test]
+    fn no_color() {
+        assert_eq!(depth(true, &[("NO_COLOR", "1")]), NoColor);
+        assert_eq!(depth(true, &[("NO_COLOR", "")]), Ansi16);
+        assert_eq!(
+            depth(true, &[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]),
+            NoColor
+        );
+        assert!(!detect(true, &[("NO_COLOR", "1")]).hyperlinks);
+        assert!(detect(true, &[("NO_COLOR", "")]).hyperlinks);
+    }
+
+    #[test]
+    fn clicolor() {
+        assert_eq!(depth(true, &[("CLICOLOR", "0")]), NoColor);
+        assert_eq!(depth(true, &[("CLICOLOR", "1")]), Ansi16);
+        assert_eq!(depth(false, &[("CLICOLOR_FORCE", "1")]), Ansi16);
+        assert_eq!(depth(false, &[("CLICOLOR_FORCE", "0")]), NoColor);
+        assert_eq!(
+            depth(true, &[("CLICOLOR", "0"), ("CLICOLOR_FORCE", "1")]),
+            Ansi16
+        );
+        let vars = [("CLICOLOR_FORCE", "1"), ("TERM", "dumb")];
+        assert_eq!(depth(true, &vars), Ansi16);
+        assert!(!detect(true, &vars).hyperlinks);
+    }
+
+    #[test]
+    fn styled_underlines() {
+        assert!(!detect(true, &[("TERM", "xterm-256color")]).styled_underlines);
+        assert!(detect(true, &[("TERM", "xterm-kitty")]).styled_underlines);
+        assert!(detect(true, &[("TERM", "foot")]).styled_underlines);
+        let vte = [("TERM", "xterm-256color"), ("VTE_VERSION", "7600")];
+        assert!(detect(true, &vte).styled_underlines);
+        let wezterm = [("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")];
+        assert!(detect(true, &wezterm).styled_underlines);
+        assert!(!detect(false, &[("TERM", "xterm-kitty")]).styled_underlines);
+    }
+
+    #[test]
+    fn transitions() {
+        assert_eq!(detect(true, &[]).transitions, Transitions::Minimal);
+        assert_eq!(detect(false, &[]).transitions, Transitions::Minimal);
+        let vars = [("TERM", "xterm-256color")];
+        assert_eq!(detect(true, &vars).transitions, Transitions::Minimal);
+        assert_eq!(Capabilities::from(Ansi16).transitions, Transitions::Reset);
+    }
+
+    #[test]
+    fn from_depth() {
+        assert_eq!(
+            Capabilities::from(Ansi256),
+            Capabilities {
+                color_depth: Ansi256,
+                hyperlinks: true,
+                titles: true,
+                transitions: Transitions::Reset,
+                styled_underlines: true,
+            }
+        );
+        assert!(!Capabilities::from(NoColor).hyperlinks);
+    }
+}
@@ -0,0 +1,397 @@
+use crate::display::AnsiGenericString;
+use crate::style::Style;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A set of styles with names, such as `error`, `path.dir` or `diff.added`,
+/// so that programs can look styles up by what they are for.
+///
+/// Names are split into parts by dots. When there’s no style for a name,
+/// the style for its parent is used instead, so `path.dir` falls back to
+/// `path` and then to the default style.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{Color, Style, Theme};
+///
+/// let mut theme = Theme::new()
+///     .with("error", Color::Red.bold())
+///     .with("path", Color::Cyan.normal())
+///     .with("path.dir", Color::Blue.bold());
+///
+/// assert_eq!(theme.style("path.dir"), Color::Blue.bold());
+/// assert_eq!(theme.style("path.file"), Color::Cyan.normal());
+/// assert_eq!(theme.style("warning"), Style::default());
+///
+/// theme.set("path.dir", Color::Blue.normal());
+/// println!("{}", theme.paint("path.dir", "src/"));
+/// ```
+///
+/// # Serialization
+///
+/// With the `derive_serde_style` feature, a theme is serialized as a map
+/// from names to styles, each written in the format of
+/// [`serde_human::style`](crate::serde_human::style).
+///
+/// When deserializing, the value for a name can also be a group of the
+/// styles for the names under it, with the style for the name itself, if it
+/// has one, under the key `style`. So this TOML:
+///
+/// ```toml
+/// error = "bold red"
+///
+/// [path]
+/// style = { fg = "cyan" }
+/// dir = "bold blue"
+/// ```
+///
+/// sets `error`, `path` and `path.dir`. Names with dots in them work too,
+/// so `"path.dir" = "bold blue"` is the same as the last line, and is the
+/// way to set a style for a name that ends in `.style`. Every other key in
+/// a group is the name of a style under it, and a group must not be empty.
+///
+/// An object style, such as `{ fg = "red", bold = true }`, has to go under
+/// the `style` key. A group whose keys are all the names of style fields,
+/// such as `fg` and `bold`, is an error rather than a set of names, since
+/// it was almost certainly meant as a style. Such names can still be set
+/// next to other names, or with dots, as in `"ui.background"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    styles: BTreeMap<String, Style>,
+}
+
+impl Theme {
+    /// Returns a theme without any styles.
+    pub fn new() -> Theme {
+        Theme::default()
+    }
+
+    /// Returns this theme with the style for `name` set.
+    pub fn with(mut self, name: impl Into<String>, style: Style) -> Theme {
+        self.set(name, style);
+        self
+    }
+
+    /// Sets the style for `name`, returning the style it replaces.
+    pub fn set(&mut self, name: impl Into<String>, style: Style) -> Option<Style> {
+        self.styles.insert(name.into(), style)
+    }
+
+    /// Removes the style for `name`, returning it.
+    pub fn remove(&mut self, name: &str) -> Option<Style> {
+        self.styles.remove(name)
+    }
+
+    /// Returns the style set for exactly `name`, without falling back to
+    /// its parents.
+    pub fn get(&self, name: &str) -> Option<Style> {
+        self.styles.get(name).copied()
+    }
+
+    /// Returns the style for `name` or, if there isn’t one, for the nearest
+    /// of its parents that has one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, Theme};
+    ///
+    /// let theme = Theme::new().with("diff", Color::White.normal());
+    /// assert_eq!(theme.lookup("diff.added.word"), Some(Color::White.normal()));
+    /// assert_eq!(theme.lookup("error"), None);
+    /// ```
+    pub fn lookup(&self, name: &str) -> Option<Style> {
+        let mut name = name;
+        loop {
+            if let Some(style) = self.get(name) {
+                return Some(style);
+            }
+            name = &name[..name.rfind('.')?];
+        }
+    }
+
+    /// Returns the style for `name` like [`Theme::lookup`], or the default
+    /// style if neither it nor any of its parents have one.
+    pub fn style(&self, name: &str) -> Style {
+        self.lookup(name).unwrap_or_default()
+    }
+
+    /// Paints the given text with the style for `name`.
+    #[must_use]
+    pub fn paint<'a, I, S: 'a + ToOwned + ?Sized>(
+        &self,
+        name: &str,
+        input: I,
+    ) -> AnsiGenericString<'a, S>
+    where
+        I: Into<Cow<'a, S>>,
+        <S as ToOwned>::Owned: fmt::Debug,
+    {
+        self.style(name).paint(input)
+    }
+
+    /// Sets every style in `other` on this theme, replacing the ones that
+    /// are already set, so that a user’s theme can override the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::{Color, Theme};
+    ///
+    /// let mut theme = Theme::new()
+    ///     .with("error", Color::Red.normal())
+    ///     .with("hint", Color::Cyan.normal());
+    /// theme.merge(&Theme::new().with("error", Color::Red.bold()));
+    ///
+    /// assert_eq!(theme.style("error"), Color::Red.bold());
+    /// assert_eq!(theme.style("hint"), Color::Cyan.normal());
+    /// ```
+    pub fn merge(&mut self, other: &Theme) {
+        self.extend(other.iter());
+    }
+
+    /// Returns the names and styles in this theme, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Style)> {
+        self.styles
+            .iter()
+            .map(|(name, style)| (name.as_str(), *style))
+    }
+
+    /// Returns the number of styles in this theme.
+    pub fn len(&self) -> usize {
+        self.styles.len()
+    }
+
+    /// Returns whether this theme has no styles.
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+    }
+}
+
+impl<N: Into<String>> FromIterator<(N, Style)> for Theme {
+    fn from_iter<T: IntoIterator<Item = (N, Style)>>(iter: T) -> Theme {
+        let mut theme = Theme::new();
+        theme.extend(iter);
+        theme
+    }
+}
+
+impl<N: Into<String>> Extend<(N, Style)> for Theme {
+    fn extend<T: IntoIterator<Item = (N, Style)>>(&mut self, iter: T) {
+        for (name, style) in iter {
+            self.set(name, style);
+        }
+    }
+}
+
+#[cfg(feature = "derive_serde_style")]
+mod serde_impl {
+    use super::Theme;
+    use crate::serde_human::{HumanStyle, STYLE_FIELDS};
+    use crate::style::Style;
+    use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, Visitor};
+    use serde::ser::{SerializeMap, Serializer};
+    use serde::{Deserialize, Serialize};
+    use std::fmt;
+
+    impl Serialize for Theme {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for (name, style) in self.iter() {
+                map.serialize_entry(name, &HumanStyle(style))?;
+            }
+            map.end()
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Theme {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Theme, D::Error> {
+            let mut theme = Theme::new();
+            deserializer.deserialize_map(Entry {
+                theme: &mut theme,
+                name: None,
+            })?;
+            Ok(theme)
+        }
+    }
+
+    /// The value for `name` in a theme: a style, or a group of the styles
+    /// under it, which can hold its own style under the key `style`. The
+    /// whole theme is the group without a name.
+    struct Entry<'t> {
+        theme: &'t mut Theme,
+        name: Option<String>,
+    }
+
+    impl<'de, 't> DeserializeSeed<'de> for Entry<'t> {
+        type Value = ();
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    impl<'de, 't> Visitor<'de> for Entry<'t> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(
+                "a style, or a map of names to styles; \
+                 object styles must go under the reserved key `style`",
+            )
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<(), E> {
+            let style = s.parse::<Style>().map_err(E::custom)?;
+            match self.name {
+                Some(name) => {
+                    self.theme.set(name, style);
+                    Ok(())
+                }
+                None => Err(E::invalid_type(de::Unexpected::Str(s), &self)),
+            }
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+            let mut empty = true;
+            let mut only_style_fields = true;
+            while let Some(key) = map.next_key::<String>()? {
+                empty = false;
+                only_style_fields &= STYLE_FIELDS.contains(&key.as_str());
+                match &self.name {
+                    Some(name) if key == "style" => {
+                        let style = map.next_value::<HumanStyle>()?.0;
+                        self.theme.set(name.clone(), style);
+                    }
+                    name => {
+                        let name = match name {
+                            Some(name) => format!("{}.{}", name, key),
+                            None => key,
+                        };
+                        map.next_value_seed(Entry {
+                            theme: self.theme,
+                            name: Some(name),
+                        })?;
+                    }
+                }
+            }
+
+            match self.name {
+                Some(name) if empty => Err(de::Error::custom(format_args!(
+                    "the group for `{}` has neither a style nor any names under it",
+                    name
+                ))),
+                Some(name) if only_style_fields => Err(de::Error::custom(format_args!(
+                    "the group for `{}` only has style fields in it; \
+                     object styles must go under the reserved key `style`",
+                    name
+                ))),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Theme;
+    use crate::style::Color::*;
+    use crate::style::Style;
+
+    #[test]
+    fn fallback() {
+        let theme: Theme = [("path", Cyan.normal()), ("path.dir", Blue.bold())]
+            .into_iter()
+            .collect();
+        assert_eq!(theme.lookup("path.dir.hidden"), Some(Blue.bold()));
+        assert_eq!(theme.lookup("path.file"), Some(Cyan.normal()));
+        assert_eq!(theme.lookup("path"), Some(Cyan.normal()));
+        assert_eq!(theme.lookup("pathname"), None);
+        assert_eq!(theme.lookup(""), None);
+        assert_eq!(theme.get("path.file"), None);
+        assert_eq!(theme.style("error"), Style::default());
+    }
+
+    #[test]
+    fn overrides() {
+        let mut theme = Theme::new().with("error", Red.normal());
+        assert_eq!(theme.set("error", Red.bold()), Some(Red.normal()));
+        theme.merge(&Theme::new().with("hint", Green.normal()));
+        assert_eq!(
+            theme.iter().collect::<Vec<_>>(),
+            [("error", Red.bold()), ("hint", Green.normal())]
+        );
+        assert_eq!(theme.remove("error"), Some(Red.bold()));
+        assert_eq!(theme.len(), 1);
+        assert!(!theme.is_empty());
+    }
+
+    #[test]
+    fn paint() {
+        let theme = Theme::new().with("error", Red.normal());
+        assert_eq!(theme.paint("error.io", "oops"), Red.paint("oops"));
+        assert_eq!(theme.paint("hint", "psst"), Style::default().paint("psst"));
+    }
+
+    #[cfg(feature = "derive_serde_style")]
+    #[test]
+    fn serde() {
+        let theme = Theme::new()
+            .with("error", Red.bold())
+            .with("path.dir", Fixed(33).underline());
+        let json = serde_json::to_string(&theme).unwrap();
+        assert_eq!(json, r#"{"error":"bold red","path.dir":"underline 33"}"#);
+        assert_eq!(serde_json::from_str::<Theme>(&json).unwrap(), theme);
+
+        let nested = r##"{
+            "error": {"style": {"fg": "red", "bold": true}},
+            "path": {"style": "cyan", "dir": "bold blue", "file": {"exe": {"style": {"fg": "#00ff00"}}}},
+            "diff": {"added": "green"},
+            "ui": {"background": "bold blue", "fg": {"style": "dimmed"}, "border": "dimmed"}
+        }"##;
+        assert_eq!(
+            serde_json::from_str::<Theme>(nested).unwrap(),
+            Theme::new()
+                .with("error", Red.bold())
+                .with("path", Cyan.normal())
+                .with("path.dir", Blue.bold())
+                .with("path.file.exe", Rgb(0, 255, 0).normal())
+                .with("diff.added", Green.normal())
+                .with("ui.background", Blue.bold())
+                .with("ui.fg", Style::new().dimmed())
+                .with("ui.border", Style::new().dimmed())
+        );
+
+        let top_level_style = serde_json::from_str::<Theme>(r#"{"style": "bold"}"#).unwrap();
+        assert_eq!(
+            top_level_style,
+            Theme::new().with("style", Style::new().bold())
+        );
+
+        assert!(serde_json::from_str::<Theme>(r#""bold red""#).is_err());
+        assert!(serde_json::from_str::<Theme>(r#"{"error": "shiny"}"#).is_err());
+    }
+
+    #[cfg(feature = "derive_serde_style")]
+    #[test]
+    fn serde_errors() {
+        let error = |json| serde_json::from_str::<Theme>(json).unwrap_err().to_string();
+        assert!(
+            error(r#"{"path": {"style": {"fg": "cyan", "forground": "red"}}}"#)
+                .contains("unknown field `forground`")
+        );
+        assert!(error(r#"{"error": {}}"#).contains("`error` has neither a style"));
+        assert!(error(r#"{"path": {"dir": {}}}"#).contains("`path.dir` has neither a style"));
+        assert!(error(r#"{"path": {"style": "cyan"}, "path.style": {}}"#)
+            .contains("`path.style` has neither a style"));
+
+        let style_hint = "object styles must go under the reserved key `style`";
+        assert!(error(r#"{"error": {"fg": "red", "bold": true}}"#).contains(style_hint));
+        assert!(error(r#"{"error": {"bold": true, "fg": "red"}}"#).contains(style_hint));
+        assert!(error(r#"{"error": {"fg": "red", "bg": "blue"}}"#).contains(style_hint));
+        assert!(error(r#"{"path": {"dir": {"fg": 33}}}"#).contains(style_hint));
+        assert!(error(r#"{"error": {"style": "red", "blink": 1}}"#).contains(style_hint));
+    }
+}
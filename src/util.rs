@@ -1,5 +1,9 @@
-use crate::display::{AnsiString, AnsiStrings};
-use std::ops::Deref;
+use crate::display::{AnsiString, AnsiStrings, OSControl};
+use crate::parse::{Token, Tokens};
+use std::borrow::Cow;
+use std::ops::{Deref, Range};
+#[cfg(feature = "unicode_width")]
+use {unicode_segmentation::UnicodeSegmentation, unicode_width::UnicodeWidthChar};
 
 /// Return a substring of the given AnsiStrings sequence, while keeping the formatting.
 pub fn sub_string(start: usize, len: usize, strs: &AnsiStrings) -> Vec<AnsiString<'static>> {
@@ -33,6 +37,152 @@ pub fn sub_string(start: usize, len: usize, strs: &AnsiStrings) -> Vec<AnsiStrin
     vec
 }
 
+/// The pieces a string gets split into when taking a substring, as the byte
+/// range of each piece along with how many positions it takes up.
+type Units<'s> = Box<dyn Iterator<Item = (Range<usize>, usize)> + 's>;
+
+fn char_units(s: &str) -> Units<'_> {
+    Box::new(s.char_indices().map(|(i, c)| (i..i + c.len_utf8(), 1)))
+}
+
+#[cfg(feature = "unicode_width")]
+fn grapheme_units(s: &str) -> Units<'_> {
+    Box::new(s.grapheme_indices(true).map(|(i, g)| (i..i + g.len(), 1)))
+}
+
+#[cfg(feature = "unicode_width")]
+fn column_units(s: &str) -> Units<'_> {
+    Box::new(
+        s.grapheme_indices(true)
+            .map(|(i, g)| (i..i + g.len(), grapheme_width(g))),
+    )
+}
+
+/// Copy part of an `AnsiString`, keeping its style and any hyperlink.
+pub(crate) fn fragment(i: &AnsiString, text: &str) -> AnsiString<'static> {
+    let copy = i.style_ref().paint(text.to_owned());
+    match i.url_string() {
+        Some(url) => copy.hyperlink(url.to_owned()),
+        None => copy,
+    }
+}
+
+/// Take the pieces that lie entirely within `start..start + len` out of each
+/// string in `strs`, where `units` decides what the pieces are and how many
+/// positions each one takes up. A piece that takes up no positions belongs
+/// to the substring that starts where it is.
+fn sub_string_by(
+    start: usize,
+    len: usize,
+    strs: &AnsiStrings,
+    units: fn(&str) -> Units<'_>,
+) -> Vec<AnsiString<'static>> {
+    let end = start.saturating_add(len);
+    let mut vec = Vec::new();
+    let mut pos = 0;
+
+    for i in strs.0.iter() {
+        if matches!(i.oscontrol, Some(OSControl::Title)) {
+            continue;
+        }
+        if pos >= end {
+            break;
+        }
+
+        let mut taken: Option<Range<usize>> = None;
+        for (bytes, size) in units(&i.string) {
+            let inside = pos >= start && pos + size <= end && (size > 0 || pos < end);
+            pos += size;
+            if inside {
+                taken = match taken {
+                    Some(range) => Some(range.start..bytes.end),
+                    None => Some(bytes),
+                };
+            } else if pos > end {
+                break;
+            }
+        }
+
+        if let Some(range) = taken {
+            vec.push(fragment(i, &i.string[range]));
+        }
+    }
+
+    vec
+}
+
+/// Return a substring of the given AnsiStrings sequence, while keeping the
+/// formatting and any hyperlinks. Unlike [`sub_string`], `start` and `len`
+/// count `char`s rather than bytes, so this never cuts a character in half.
+///
+/// Titles are not part of the displayed text, so they are left out.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{sub_string_chars, AnsiStrings, Color::{Blue, Red}};
+///
+/// let strings = [Red.paint("übung"), Blue.paint("ß")];
+/// let cut = sub_string_chars(3, 3, &AnsiStrings(&strings));
+/// assert_eq!(cut, [Red.paint("ng"), Blue.paint("ß")]);
+/// ```
+pub fn sub_string_chars(start: usize, len: usize, strs: &AnsiStrings) -> Vec<AnsiString<'static>> {
+    sub_string_by(start, len, strs, char_units)
+}
+
+/// Return a substring of the given AnsiStrings sequence, while keeping the
+/// formatting and any hyperlinks. `start` and `len` count extended grapheme
+/// clusters, so a character is never separated from its combining marks.
+///
+/// Titles are not part of the displayed text, so they are left out.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{sub_string_graphemes, AnsiStrings, Color::Red};
+///
+/// let strings = [Red.paint("cafe\u{301}s")];
+/// let cut = sub_string_graphemes(3, 1, &AnsiStrings(&strings));
+/// assert_eq!(cut, [Red.paint("e\u{301}")]);
+/// ```
+#[cfg(feature = "unicode_width")]
+pub fn sub_string_graphemes(
+    start: usize,
+    len: usize,
+    strs: &AnsiStrings,
+) -> Vec<AnsiString<'static>> {
+    sub_string_by(start, len, strs, grapheme_units)
+}
+
+/// Return a substring of the given AnsiStrings sequence, while keeping the
+/// formatting and any hyperlinks. `start` and `len` are measured in terminal
+/// columns, as with [`unstyled_width`].
+///
+/// Only grapheme clusters that fit entirely within the columns are included,
+/// so a wide character that straddles either edge is left out, and the result
+/// can be narrower than `len`. Titles are left out as well.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{sub_string_columns, AnsiStrings, Color::{Blue, Red}};
+///
+/// let strings = [Red.paint("日本"), Blue.paint("語!")];
+/// let cut = sub_string_columns(2, 4, &AnsiStrings(&strings));
+/// assert_eq!(cut, [Red.paint("本"), Blue.paint("語")]);
+///
+/// let cut = sub_string_columns(1, 4, &AnsiStrings(&strings));
+/// assert_eq!(cut, [Red.paint("本")]);
+/// ```
+#[cfg(feature = "unicode_width")]
+pub fn sub_string_columns(
+    start: usize,
+    len: usize,
+    strs: &AnsiStrings,
+) -> Vec<AnsiString<'static>> {
+    sub_string_by(start, len, strs, column_units)
+}
+
 /// Return a concatenated copy of `strs` without the formatting, as an allocated `String`.
 pub fn unstyle(strs: &AnsiStrings) -> String {
     let mut s = String::new();
@@ -53,6 +203,134 @@ pub fn unstyled_len(strs: &AnsiStrings) -> usize {
     l
 }
 
+/// Return the number of terminal columns taken up by a grapheme cluster.
+///
+/// A cluster is as wide as the character it starts with, since terminals
+/// draw combining marks and the rest of an emoji sequence joined by
+/// zero-width joiners over that one, except that a variation selector 16
+/// asks for an emoji presentation, which is two columns wide. Control
+/// characters don’t take up any columns of their own, so they count as zero,
+/// as does anything else with no width.
+#[cfg(feature = "unicode_width")]
+pub(crate) fn grapheme_width(grapheme: &str) -> usize {
+    match grapheme.chars().next() {
+        Some(c) if !c.is_control() => {
+            let width = c.width().unwrap_or(0);
+            if width > 0 && grapheme.contains('\u{fe0f}') {
+                2
+            } else {
+                width
+            }
+        }
+        _ => 0,
+    }
+}
+
+/// Return the number of terminal columns taken up by a string, measured one
+/// grapheme cluster at a time.
+#[cfg(feature = "unicode_width")]
+pub(crate) fn str_width(s: &str) -> usize {
+    s.graphemes(true).map(grapheme_width).sum()
+}
+
+#[cfg(feature = "unicode_width")]
+impl<'a> AnsiString<'a> {
+    /// Return the number of terminal columns the text of this string takes
+    /// up when displayed, using the Unicode East Asian Width property and
+    /// grapheme cluster boundaries. Wide characters such as CJK ideographs
+    /// and most emoji count as two columns, and combining marks as none.
+    ///
+    /// Titles are not displayed in the terminal text area, so they have a
+    /// width of zero.
+    ///
+    /// Control characters count as zero columns too. That includes tabs,
+    /// since how far a tab moves depends on the column it starts in, so text
+    /// with tabs should have them expanded to spaces before it is measured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nu_ansi_term::Color::Red;
+    ///
+    /// assert_eq!(Red.paint("日本語").width(), 6);
+    /// assert_eq!(Red.paint("e\u{301}").width(), 1);
+    /// assert_eq!(Red.paint("a\tb").width(), 2);
+    /// assert_eq!(Red.paint("a\tb".replace('\t', "    ")).width(), 6);
+    /// ```
+    pub fn width(&self) -> usize {
+        match self.oscontrol {
+            Some(OSControl::Title) => 0,
+            _ => str_width(&self.string),
+        }
+    }
+}
+
+/// Return the unstyled display width of AnsiStrings, in terminal columns.
+/// Unlike [`unstyled_len`], which counts bytes, this counts wide characters
+/// as two columns and combining marks as none. Tabs count as none as well,
+/// so expand them first. See [`AnsiString::width`].
+#[cfg(feature = "unicode_width")]
+pub fn unstyled_width(strs: &AnsiStrings) -> usize {
+    strs.0.iter().map(AnsiString::width).sum()
+}
+
+/// Remove every escape sequence from a string, leaving only the text that
+/// would be displayed.
+///
+/// This removes CSI sequences (including colors and styles), OSC sequences
+/// terminated by either BEL or ST (such as hyperlinks and titles), DCS, SOS,
+/// PM and APC strings, and single-character escapes. The text of a hyperlink
+/// is kept, while its URL is removed.
+///
+/// Returns a borrowed value when there is nothing to remove, so calling this
+/// on plain text does not allocate.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::{strip_ansi, Color::Red};
+///
+/// let red = Red.paint("red").to_string();
+/// assert_eq!(strip_ansi(&red), "red");
+/// ```
+pub fn strip_ansi(input: &str) -> Cow<'_, str> {
+    if !input.contains('\x1B') {
+        return Cow::Borrowed(input);
+    }
+
+    let mut stripped = String::with_capacity(input.len());
+    for token in Tokens::new(input.as_bytes()) {
+        if let Token::Text(range) = token {
+            stripped.push_str(&input[range]);
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+/// Remove every escape sequence from a byte string. This is the same as
+/// [`strip_ansi`], for text in an unknown encoding.
+///
+/// # Examples
+///
+/// ```
+/// use nu_ansi_term::strip_ansi_bytes;
+///
+/// assert_eq!(strip_ansi_bytes(b"\x1B[1mbold\x1B[0m"), &b"bold"[..]);
+/// ```
+pub fn strip_ansi_bytes(input: &[u8]) -> Cow<'_, [u8]> {
+    if !input.contains(&0x1B) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut stripped = Vec::with_capacity(input.len());
+    for token in Tokens::new(input) {
+        if let Token::Text(range) = token {
+            stripped.extend_from_slice(&input[range]);
+        }
+    }
+    Cow::Owned(stripped)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -72,4 +350,118 @@ mod test {
         let l2 = [Black.paint("st"), Red.paint("-second"), White.paint("-t")];
         assert_eq!(sub_string(3, 11, &a), l2);
     }
+
+    #[test]
+    fn sub_string_by_chars() {
+        let l = [
+            Red.paint("añb"),
+            Blue.paint("çd").hyperlink("https://example.com"),
+            crate::AnsiGenericString::title("title"),
+            Green.paint("é"),
+        ];
+        let a = AnsiStrings(&l);
+        assert_eq!(
+            sub_string_chars(1, 4, &a),
+            [
+                Red.paint("ñb"),
+                Blue.paint("çd").hyperlink("https://example.com"),
+            ]
+        );
+        assert_eq!(
+            sub_string_chars(4, 10, &a),
+            [
+                Blue.paint("d").hyperlink("https://example.com"),
+                Green.paint("é")
+            ]
+        );
+        assert_eq!(sub_string_chars(2, 0, &a), []);
+        assert_eq!(sub_string_chars(10, 2, &a), []);
+        assert_eq!(sub_string_chars(0, usize::MAX, &a).len(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode_width")]
+    fn sub_string_by_graphemes() {
+        let l = [Red.paint("e\u{301}e\u{301}"), Blue.paint("🇯🇵x")];
+        let a = AnsiStrings(&l);
+        assert_eq!(
+            sub_string_graphemes(1, 2, &a),
+            [Red.paint("e\u{301}"), Blue.paint("🇯🇵")]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "unicode_width")]
+    fn sub_string_by_columns() {
+        let l = [
+            Red.paint("a日b"),
+            Blue.paint("\u{200b}c"),
+            Green.paint("本"),
+        ];
+        let a = AnsiStrings(&l);
+        assert_eq!(sub_string_columns(0, 2, &a), [Red.paint("a")]);
+        assert_eq!(sub_string_columns(1, 2, &a), [Red.paint("日")]);
+        assert_eq!(
+            sub_string_columns(3, 2, &a),
+            [Red.paint("b"), Blue.paint("\u{200b}c")]
+        );
+        assert_eq!(sub_string_columns(3, 1, &a), [Red.paint("b")]);
+        assert_eq!(sub_string_columns(4, 1, &a), [Blue.paint("\u{200b}c")]);
+        assert_eq!(sub_string_columns(5, 2, &a), [Green.paint("本")]);
+        assert_eq!(sub_string_columns(6, 2, &a), []);
+    }
+
+    #[test]
+    #[cfg(feature = "unicode_width")]
+    fn width() {
+        let l = [
+            Red.paint("日本"),
+            Blue.paint("e\u{301}"),
+            crate::AnsiGenericString::title("title"),
+            Green.paint("👨\u{200d}👩\u{200d}👧"),
+            White.paint("\tx"),
+            Red.paint("\u{2764}\u{fe0f}"),
+        ];
+        let a = AnsiStrings(&l);
+        assert_eq!(unstyled_width(&a), 10);
+        assert_eq!(unstyled_len(&a), 40);
+        assert_eq!(l[2].width(), 0);
+        assert_eq!(l[4].width(), 1);
+        assert_eq!(l[5].width(), 2);
+        assert_eq!(unstyled_width(&AnsiStrings(&[Red.paint("a\tb")])), 2);
+    }
+
+    #[test]
+    fn strip() {
+        let l = [
+            Red.bold().paint("first"),
+            Blue.paint("-link").hyperlink("https://example.com"),
+            crate::AnsiGenericString::title("title"),
+            Fixed(100).on(Rgb(1, 2, 3)).paint("-third"),
+        ];
+        assert_eq!(strip_ansi(&AnsiStrings(&l).to_string()), "first-link-third");
+
+        assert_eq!(
+            strip_ansi("a\x1B]8;;url\x07b\x1B]8;;\x07c\x1BP1$r\x1B\\d\x1B7e\x1B(Bf\x1B"),
+            "abcdef"
+        );
+        assert_eq!(strip_ansi("\x1B[31m日本\x1B[0m語"), "日本語");
+    }
+
+    #[test]
+    fn strip_borrows_plain_text() {
+        assert!(matches!(strip_ansi("plain"), Cow::Borrowed("plain")));
+        assert!(matches!(
+            strip_ansi_bytes(b"plain"),
+            Cow::Borrowed(b"plain")
+        ));
+        assert!(matches!(strip_ansi("\x1B[1m"), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn strip_bytes() {
+        let mut v = Vec::new();
+        Green.paint(&b"\xFFbytes"[..]).write_to(&mut v).unwrap();
+        assert_eq!(strip_ansi_bytes(&v), &b"\xFFbytes"[..]);
+    }
 }
@@ -0,0 +1,55 @@
+use nu_ansi_term::{
+    colors_enabled, no_color, set_colors_enabled, AnsiGenericString, AnsiStrings, Color::*,
+    ColorDepth,
+};
+
+// The switch is global, so everything that depends on it is checked in a
+// single test, in a binary of its own.
+#[test]
+fn global_switch() {
+    let l = [
+        Red.paint("red"),
+        Blue.paint("link").hyperlink("https://example.com"),
+        AnsiGenericString::title("Title"),
+    ];
+
+    // The default comes from NO_COLOR, read when it’s first needed.
+    std::env::set_var("NO_COLOR", "1");
+    assert!(no_color());
+    assert!(!colors_enabled());
+    std::env::remove_var("NO_COLOR");
+    assert!(!colors_enabled());
+
+    set_colors_enabled(false);
+    assert_eq!(Red.prefix().to_string(), "");
+    assert_eq!(Red.infix(Blue).to_string(), "");
+    assert_eq!(Red.suffix().to_string(), "");
+    assert_eq!(l[0].to_string(), "red");
+    assert_eq!(l[1].to_string(), "link");
+    assert_eq!(AnsiStrings(&l).to_string(), "redlink");
+
+    let mut v = Vec::new();
+    Red.paint(&b"bytes"[..]).write_to(&mut v).unwrap();
+    assert_eq!(v, b"bytes");
+
+    assert_eq!(
+        Red.prefix().render(ColorDepth::Ansi16).to_string(),
+        "\x1B[31m"
+    );
+    assert_eq!(
+        Red.infix(Blue).render(ColorDepth::Ansi16).to_string(),
+        "\x1B[34m"
+    );
+    assert_eq!(
+        l[0].render(ColorDepth::TrueColor).to_string(),
+        "\x1B[31mred\x1B[0m"
+    );
+
+    set_colors_enabled(true);
+    assert!(colors_enabled());
+    assert_eq!(Red.prefix().to_string(), "\x1B[31m");
+    assert_eq!(
+        AnsiStrings(&l).to_string(),
+        "\x1B[31mred\x1B[34m\x1B]8;;https://example.com\x1B\\link\x1B]8;;\x1B\\\x1B[0m\x1B]2;Title\x1B\\"
+    );
+}
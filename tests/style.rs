@@ -1,4 +1,4 @@
-use nu_ansi_term::Style;
+use nu_ansi_term::{Attributes, Style, UnderlineStyle};
 
 #[test]
 fn manual_instance_style() {
@@ -6,7 +6,7 @@ fn manual_instance_style() {
     assert_eq!(Style::default(), s);
 
     let s = Style {
-        is_underline: false,
+        attributes: Attributes::empty(),
         ..Style::default()
     };
     assert_eq!(Style::default(), s);
@@ -14,15 +14,20 @@ fn manual_instance_style() {
     let s = Style {
         foreground: None,
         background: None,
-        is_bold: false,
-        is_dimmed: false,
-        is_italic: false,
-        is_underline: false,
-        is_blink: false,
-        is_reverse: false,
-        is_hidden: false,
-        is_strikethrough: false,
+        underline_color: None,
+        attributes: Attributes::empty(),
+        underline_style: UnderlineStyle::Single,
         prefix_with_reset: false,
     };
     assert_eq!(Style::default(), s);
 }
+
+#[test]
+fn attributes_instead_of_flags() {
+    let s = Style {
+        attributes: Attributes::BOLD | Attributes::UNDERLINE,
+        ..Style::default()
+    };
+    assert_eq!(Style::new().bold().underline(), s);
+    assert!(s.is_bold() && s.is_underline() && !s.is_italic());
+}